@@ -1,12 +1,36 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20241206_000001_create_initial_tables;
+mod m20261018_000001_add_full_text_search;
+mod m20261018_000002_add_events_happening_at_index;
+mod m20261018_000003_add_user_subscriptions_user_id_index;
+mod m20261018_000004_add_user_subscriptions_disabled;
+mod m20261018_000005_add_user_subscriptions_last_notified_at;
+mod m20261018_000006_create_user_sessions;
+mod m20261018_000007_add_user_sessions_expiry;
+mod m20261018_000008_add_users_role_and_audit_logs;
+mod m20261018_000009_create_scheduled_jobs;
+mod m20261018_000010_create_crawl_runs;
+mod m20261018_000011_add_crawl_runs_jobs;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20241206_000001_create_initial_tables::Migration)]
+        vec![
+            Box::new(m20241206_000001_create_initial_tables::Migration),
+            Box::new(m20261018_000001_add_full_text_search::Migration),
+            Box::new(m20261018_000002_add_events_happening_at_index::Migration),
+            Box::new(m20261018_000003_add_user_subscriptions_user_id_index::Migration),
+            Box::new(m20261018_000004_add_user_subscriptions_disabled::Migration),
+            Box::new(m20261018_000005_add_user_subscriptions_last_notified_at::Migration),
+            Box::new(m20261018_000006_create_user_sessions::Migration),
+            Box::new(m20261018_000007_add_user_sessions_expiry::Migration),
+            Box::new(m20261018_000008_add_users_role_and_audit_logs::Migration),
+            Box::new(m20261018_000009_create_scheduled_jobs::Migration),
+            Box::new(m20261018_000010_create_crawl_runs::Migration),
+            Box::new(m20261018_000011_add_crawl_runs_jobs::Migration),
+        ]
     }
 }
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            CREATE EXTENSION IF NOT EXISTS unaccent;
+
+            -- unaccent() is only STABLE, generated columns and indexes need an IMMUTABLE wrapper
+
+            CREATE OR REPLACE FUNCTION immutable_unaccent(TEXT)
+                RETURNS TEXT
+                LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+                AS $$ SELECT public.unaccent('public.unaccent'::regdictionary, $1) $$;
+
+            -- SEARCH VECTORS
+
+            ALTER TABLE events
+                ADD COLUMN search_vector TSVECTOR
+                GENERATED ALWAYS AS (
+                    setweight(to_tsvector('simple', immutable_unaccent(name)), 'A')
+                ) STORED;
+            ALTER TABLE organizers
+                ADD COLUMN search_vector TSVECTOR
+                GENERATED ALWAYS AS (
+                    setweight(to_tsvector('simple', immutable_unaccent(name)), 'A')
+                    || setweight(to_tsvector('simple', immutable_unaccent(city)), 'B')
+                    || setweight(to_tsvector('simple', immutable_unaccent(address)), 'C')
+                ) STORED;
+
+            -- INDEXES
+
+            CREATE INDEX idx_events_search_vector
+                ON events USING GIN (search_vector);
+            CREATE INDEX idx_organizers_search_vector
+                ON organizers USING GIN (search_vector);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP INDEX IF EXISTS idx_organizers_search_vector;
+            DROP INDEX IF EXISTS idx_events_search_vector;
+            ALTER TABLE organizers DROP COLUMN IF EXISTS search_vector;
+            ALTER TABLE events DROP COLUMN IF EXISTS search_vector;
+            DROP FUNCTION IF EXISTS immutable_unaccent(TEXT);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE user_subscriptions
+                ADD COLUMN disabled_at TIMESTAMPTZ,
+                ADD COLUMN disabled_reason TEXT;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE user_subscriptions
+                DROP COLUMN IF EXISTS disabled_reason,
+                DROP COLUMN IF EXISTS disabled_at;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
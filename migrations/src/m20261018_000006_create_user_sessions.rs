@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            -- USER SESSIONS
+
+            CREATE TABLE user_sessions (
+                id          SERIAL PRIMARY KEY,
+                user_id     INTEGER NOT NULL,
+                token_hash  TEXT NOT NULL,
+                created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+            );
+
+            ALTER TABLE user_sessions
+                ADD CONSTRAINT fk_user_sessions_user_id
+                FOREIGN KEY (user_id) REFERENCES users (id);
+            ALTER TABLE user_sessions
+                ADD CONSTRAINT uk_user_sessions_token_hash
+                UNIQUE (token_hash);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS user_sessions;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE user_sessions
+                ADD COLUMN expires_at TIMESTAMPTZ NOT NULL DEFAULT now() + INTERVAL '30 days',
+                ADD COLUMN revoked_at TIMESTAMPTZ;
+
+            CREATE INDEX idx_user_sessions_user_id
+                ON user_sessions (user_id);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP INDEX IF EXISTS idx_user_sessions_user_id;
+
+            ALTER TABLE user_sessions
+                DROP COLUMN IF EXISTS revoked_at,
+                DROP COLUMN IF EXISTS expires_at;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE users
+                ADD COLUMN role TEXT NOT NULL DEFAULT 'user';
+            ALTER TABLE users
+                ADD CONSTRAINT ck_users_role
+                CHECK (role IN ('user', 'admin'));
+
+            -- AUDIT LOGS
+
+            CREATE TABLE audit_logs (
+                id            SERIAL PRIMARY KEY,
+                user_id       INTEGER,
+                api_key_name  TEXT,
+                action        TEXT NOT NULL,
+                details       JSONB,
+                created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+            );
+
+            ALTER TABLE audit_logs
+                ADD CONSTRAINT fk_audit_logs_user_id
+                FOREIGN KEY (user_id) REFERENCES users (id);
+
+            CREATE INDEX idx_audit_logs_created_at
+                ON audit_logs (created_at);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS audit_logs;
+
+            ALTER TABLE users
+                DROP CONSTRAINT IF EXISTS ck_users_role,
+                DROP COLUMN IF EXISTS role;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
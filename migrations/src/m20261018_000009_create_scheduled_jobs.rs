@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            -- SCHEDULED JOBS
+
+            CREATE TABLE scheduled_jobs (
+                name         TEXT PRIMARY KEY,
+                last_run_at  TIMESTAMPTZ NOT NULL
+            );
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS scheduled_jobs;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
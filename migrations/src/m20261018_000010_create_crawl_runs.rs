@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            -- CRAWL RUNS
+
+            CREATE TABLE crawl_runs (
+                id                  SERIAL PRIMARY KEY,
+                source              TEXT NOT NULL,
+                started_at          TIMESTAMPTZ NOT NULL DEFAULT now(),
+                finished_at         TIMESTAMPTZ,
+                rows_read           INTEGER NOT NULL DEFAULT 0,
+                rows_skipped        INTEGER NOT NULL DEFAULT 0,
+                skipped_by_reason   JSONB NOT NULL DEFAULT '{}',
+                organizers_created  INTEGER NOT NULL DEFAULT 0,
+                events_inserted     INTEGER NOT NULL DEFAULT 0,
+                events_updated      INTEGER NOT NULL DEFAULT 0,
+                error               TEXT
+            );
+
+            CREATE INDEX idx_crawl_runs_started_at
+                ON crawl_runs (started_at);
+
+            -- CRAWL REJECTIONS
+
+            CREATE TABLE crawl_rejections (
+                id             SERIAL PRIMARY KEY,
+                crawl_run_id   INTEGER NOT NULL,
+                record_number  INTEGER NOT NULL,
+                reason         TEXT NOT NULL,
+                error          TEXT NOT NULL,
+                raw            TEXT,
+                created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+            );
+
+            ALTER TABLE crawl_rejections
+                ADD CONSTRAINT fk_crawl_rejections_crawl_run_id
+                FOREIGN KEY (crawl_run_id) REFERENCES crawl_runs (id) ON DELETE CASCADE;
+
+            CREATE INDEX idx_crawl_rejections_crawl_run_id
+                ON crawl_rejections (crawl_run_id, id);
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS crawl_rejections;
+            DROP TABLE IF EXISTS crawl_runs;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE crawl_runs
+                ADD COLUMN trigger TEXT,
+                ADD COLUMN source_config JSONB,
+                ADD COLUMN cancel_requested_at TIMESTAMPTZ;
+
+            CREATE INDEX idx_crawl_runs_running
+                ON crawl_runs (started_at)
+                WHERE finished_at IS NULL;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP INDEX IF EXISTS idx_crawl_runs_running;
+
+            ALTER TABLE crawl_runs
+                DROP COLUMN IF EXISTS cancel_requested_at,
+                DROP COLUMN IF EXISTS source_config,
+                DROP COLUMN IF EXISTS trigger;
+        "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::anyhow;
+use axum::{
+    Extension,
+    extract::{FromRequestParts, Request},
+    http::{HeaderMap, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use tracing::warn;
+
+use crate::Connections;
+use crate::entities::{user_sessions, users};
+use crate::error::ApiError;
+use crate::services::admin::access_service::{self, Admin, ApiKeys};
+use crate::services::users::session_service;
+
+/// Carries one of the `ADMIN_API_KEYS`, see [`CurrentAdmin`].
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Carries the session token signed by [`session_service::cookie_value`] for browser clients.
+pub const SESSION_COOKIE: &str = "session";
+
+/// Outcome of resolving the request's credentials, stored in the request extensions by
+/// [`authenticate`].
+#[derive(Debug, Clone)]
+pub enum Authentication {
+    Anonymous,
+    /// A bearer token was sent but it is unknown, expired or revoked.
+    Invalid,
+    Session(Box<CurrentSession>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Credentials {
+    Bearer(String),
+    Cookie(String),
+}
+
+/// Resolves the session of every request from its `Authorization: Bearer <token>` header or,
+/// when there is none, from the session cookie.
+///
+/// A cookie that is invalid, cannot be verified or belongs to an expired session is treated as
+/// anonymous, so that a stale cookie does not break the public endpoints.
+pub async fn authenticate(
+    Extension(conns): Extension<Connections>,
+    jar: CookieJar,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let authentication = match resolve(&conns, credentials(request.headers(), &jar)).await {
+        Ok(authentication) => authentication,
+        Err(err) => return err.into_response(),
+    };
+    request.extensions_mut().insert(authentication);
+
+    next.run(request).await
+}
+
+async fn resolve(
+    conns: &Connections,
+    credentials: Option<Credentials>,
+) -> Result<Authentication, ApiError> {
+    let (token, invalid) = match credentials {
+        None => return Ok(Authentication::Anonymous),
+        Some(Credentials::Bearer(token)) => (token, Authentication::Invalid),
+        Some(Credentials::Cookie(value)) => match session_service::token_from_cookie(&value) {
+            Ok(Some(token)) => (token, Authentication::Anonymous),
+            Ok(None) => return Ok(Authentication::Anonymous),
+            // E.g. without `APP_SECRET`, which must not take the public endpoints down with it.
+            Err(err) => {
+                warn!(error = %err, "ignoring session cookie that cannot be verified");
+                return Ok(Authentication::Anonymous);
+            }
+        },
+    };
+
+    Ok(match session_service::authenticate(conns, &token).await? {
+        Some((session, user)) => {
+            Authentication::Session(Box::new(CurrentSession { session, user }))
+        }
+        None => invalid,
+    })
+}
+
+/// The bearer token takes precedence so that API clients are not affected by browser cookies.
+fn credentials(headers: &HeaderMap, jar: &CookieJar) -> Option<Credentials> {
+    if let Some(token) = bearer_token(headers) {
+        return Some(Credentials::Bearer(token.to_string()));
+    }
+
+    jar.get(SESSION_COOKIE)
+        .map(|cookie| cookie.value())
+        .filter(|value| !value.is_empty())
+        .map(|value| Credentials::Cookie(value.to_string()))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+impl Authentication {
+    fn from_parts(parts: &Parts) -> Result<&Self, ApiError> {
+        parts
+            .extensions
+            .get::<Authentication>()
+            .ok_or_else(|| anyhow!("authentication middleware is not installed").into())
+    }
+}
+
+/// The user signed in by a bearer token or the session cookie.
+///
+/// Handlers taking this extractor respond with `401 Unauthorized` to anonymous requests.
+pub struct CurrentUser(pub users::Model);
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let CurrentSession { user, .. } = CurrentSession::from_request_parts(parts, state).await?;
+
+        Ok(CurrentUser(user))
+    }
+}
+
+/// Like [`CurrentUser`] for handlers that also serve anonymous requests.
+///
+/// An invalid bearer token is still rejected with `401 Unauthorized` rather than treated as
+/// anonymous.
+pub struct OptionalUser(pub Option<users::Model>);
+
+impl<S> FromRequestParts<S> for OptionalUser
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match Authentication::from_parts(parts)? {
+            Authentication::Anonymous => Ok(OptionalUser(None)),
+            Authentication::Invalid => Err(invalid_token()),
+            Authentication::Session(current) => Ok(OptionalUser(Some(current.user.clone()))),
+        }
+    }
+}
+
+/// Like [`CurrentUser`] for handlers that act on the session itself, e.g. to sign out.
+#[derive(Debug, Clone)]
+pub struct CurrentSession {
+    pub session: user_sessions::Model,
+    pub user: users::Model,
+}
+
+impl<S> FromRequestParts<S> for CurrentSession
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match Authentication::from_parts(parts)? {
+            Authentication::Anonymous => Err(ApiError::unauthorized(anyhow!(
+                "missing bearer token or session cookie"
+            ))),
+            Authentication::Invalid => Err(invalid_token()),
+            Authentication::Session(current) => Ok(current.as_ref().clone()),
+        }
+    }
+}
+
+/// A user with the admin role or a request with a valid `X-Api-Key` header.
+///
+/// Responds with `401 Unauthorized` to anonymous requests and invalid API keys and with
+/// `403 Forbidden` to other users.
+pub struct CurrentAdmin(pub Admin);
+
+impl<S> FromRequestParts<S> for CurrentAdmin
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(key) = parts.headers.get(API_KEY_HEADER) {
+            let name = key
+                .to_str()
+                .ok()
+                .and_then(|key| ApiKeys::from_env().find(key).map(str::to_string))
+                .ok_or_else(|| ApiError::unauthorized(anyhow!("invalid API key")))?;
+
+            return Ok(CurrentAdmin(Admin::ApiKey(name)));
+        }
+
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+        if !access_service::is_admin(&user) {
+            return Err(ApiError::forbidden(anyhow!("admin role is required")));
+        }
+
+        Ok(CurrentAdmin(Admin::User(user)))
+    }
+}
+
+fn invalid_token() -> ApiError {
+    ApiError::unauthorized(anyhow!("invalid or expired session token"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use axum_extra::extract::cookie::Cookie;
+
+    #[test]
+    fn test_credentials() {
+        let mut headers = HeaderMap::new();
+        let jar = CookieJar::new();
+        assert_eq!(credentials(&headers, &jar), None);
+
+        let jar = jar.add(Cookie::new(SESSION_COOKIE, "cookie.signature"));
+        assert_eq!(
+            credentials(&headers, &jar),
+            Some(Credentials::Cookie("cookie.signature".to_string()))
+        );
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert_eq!(
+            credentials(&headers, &jar),
+            Some(Credentials::Cookie("cookie.signature".to_string()))
+        );
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer abc"),
+        );
+        assert_eq!(
+            credentials(&headers, &jar),
+            Some(Credentials::Bearer("abc".to_string()))
+        );
+    }
+}
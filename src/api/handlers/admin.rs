@@ -0,0 +1,118 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query},
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::Connections;
+use crate::api::auth::CurrentAdmin;
+use crate::error::ApiError;
+use crate::services::admin::crawl_runs_service::{self, CrawlRejection, CrawlRun};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CrawlRunsQuery {
+    /// Defaults to 20.
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CrawlRejectionsQuery {
+    /// Id of the last rejection of the previous page.
+    pub after_id: Option<i32>,
+    /// Defaults to 100.
+    pub limit: Option<u64>,
+}
+
+/// Most recent crawl runs first.
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    path = "/admin/crawl-runs",
+    operation_id = "listCrawlRuns",
+    security(("bearer" = []), ("api_key" = [])),
+    params(CrawlRunsQuery),
+    responses(
+        (status = OK, body = Vec<CrawlRun>),
+        (status = UNAUTHORIZED),
+        (status = FORBIDDEN),
+    ),
+)]
+pub async fn crawl_runs(
+    Extension(conns): Extension<Connections>,
+    _admin: CurrentAdmin,
+    Query(query): Query<CrawlRunsQuery>,
+) -> Result<Json<Vec<CrawlRun>>, ApiError> {
+    Ok(Json(
+        crawl_runs_service::list(&conns, query.limit.unwrap_or(20)).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    path = "/admin/crawl-runs/last",
+    operation_id = "lastCrawlRun",
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = OK, body = CrawlRun),
+        (status = UNAUTHORIZED),
+        (status = FORBIDDEN),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn last_crawl_run(
+    Extension(conns): Extension<Connections>,
+    _admin: CurrentAdmin,
+) -> Result<Json<CrawlRun>, ApiError> {
+    Ok(Json(crawl_runs_service::last(&conns).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    path = "/admin/crawl-runs/{id}",
+    operation_id = "showCrawlRun",
+    security(("bearer" = []), ("api_key" = [])),
+    params(("id" = i32, Path)),
+    responses(
+        (status = OK, body = CrawlRun),
+        (status = UNAUTHORIZED),
+        (status = FORBIDDEN),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn crawl_run(
+    Extension(conns): Extension<Connections>,
+    _admin: CurrentAdmin,
+    Path(id): Path<i32>,
+) -> Result<Json<CrawlRun>, ApiError> {
+    Ok(Json(crawl_runs_service::show(&conns, id).await?))
+}
+
+/// Records skipped by the crawl run, with the raw input they were read from.
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    path = "/admin/crawl-runs/{id}/rejections",
+    operation_id = "listCrawlRejections",
+    security(("bearer" = []), ("api_key" = [])),
+    params(("id" = i32, Path), CrawlRejectionsQuery),
+    responses(
+        (status = OK, body = Vec<CrawlRejection>),
+        (status = UNAUTHORIZED),
+        (status = FORBIDDEN),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn crawl_rejections(
+    Extension(conns): Extension<Connections>,
+    _admin: CurrentAdmin,
+    Path(id): Path<i32>,
+    Query(query): Query<CrawlRejectionsQuery>,
+) -> Result<Json<Vec<CrawlRejection>>, ApiError> {
+    Ok(Json(
+        crawl_runs_service::rejections(&conns, id, query.after_id, query.limit.unwrap_or(100))
+            .await?,
+    ))
+}
@@ -0,0 +1,267 @@
+use anyhow::anyhow;
+use axum::{Extension, Json, extract::Query, http::StatusCode, response::Redirect};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::Deserialize;
+use std::sync::Arc;
+use time::Duration;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::Connections;
+use crate::api::auth::{CurrentSession, CurrentUser, OptionalUser, SESSION_COOKIE};
+use crate::error::ApiError;
+use crate::services::users::discord_login_service::{self, DiscordOAuthConfig};
+use crate::services::users::google_login_service::{self, GoogleKeys, GoogleOAuthConfig};
+use crate::services::users::session_service::{self, LoginResponse, UserProfile};
+
+/// Holds the OAuth `state` between the redirect to the provider and its callback.
+const STATE_COOKIE: &str = "oauth_state";
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OAuthCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set by the provider instead of `code` when the user denied access.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GoogleIdTokenRequest {
+    /// The `credential` returned by a Sign in with Google button.
+    pub id_token: String,
+}
+
+#[utoipa::path(
+    get,
+    tag = "Auth",
+    path = "/auth/discord",
+    operation_id = "discordLogin",
+    responses(
+        (status = SEE_OTHER, description = "Redirect to Discord"),
+    ),
+)]
+pub async fn discord_login(jar: CookieJar) -> Result<(CookieJar, Redirect), ApiError> {
+    let config = DiscordOAuthConfig::from_env()?;
+    let state = new_state();
+    let url = discord_login_service::authorize_url(&config, &state)?;
+
+    Ok((jar.add(state_cookie(state)), Redirect::to(&url)))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Auth",
+    path = "/auth/discord/callback",
+    operation_id = "discordCallback",
+    params(OAuthCallbackQuery),
+    responses(
+        (status = OK, body = LoginResponse),
+        (status = BAD_REQUEST),
+        (status = UNAUTHORIZED),
+    ),
+)]
+pub async fn discord_callback(
+    Extension(conns): Extension<Connections>,
+    jar: CookieJar,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> Result<(CookieJar, Json<LoginResponse>), ApiError> {
+    let code = verify_callback(&jar, query)?;
+    let config = DiscordOAuthConfig::from_env()?;
+    let response = discord_login_service::login(&conns, &config, &code).await?;
+
+    Ok((
+        jar.remove(Cookie::build(STATE_COOKIE).path("/auth"))
+            .add(session_cookie(&response)?),
+        Json(response),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Auth",
+    path = "/auth/google",
+    operation_id = "googleLogin",
+    responses(
+        (status = SEE_OTHER, description = "Redirect to Google"),
+    ),
+)]
+pub async fn google_login(jar: CookieJar) -> Result<(CookieJar, Redirect), ApiError> {
+    let config = GoogleOAuthConfig::from_env()?;
+    let state = new_state();
+    let url = google_login_service::authorize_url(&config, &state)?;
+
+    Ok((jar.add(state_cookie(state)), Redirect::to(&url)))
+}
+
+/// Signed-in users get the Google account linked to them instead of a new account.
+#[utoipa::path(
+    get,
+    tag = "Auth",
+    path = "/auth/google/callback",
+    operation_id = "googleCallback",
+    params(OAuthCallbackQuery),
+    responses(
+        (status = OK, body = LoginResponse),
+        (status = BAD_REQUEST),
+        (status = UNAUTHORIZED),
+        (status = CONFLICT, description = "Google account is linked to another user"),
+    ),
+)]
+pub async fn google_callback(
+    Extension(conns): Extension<Connections>,
+    Extension(keys): Extension<Arc<GoogleKeys>>,
+    OptionalUser(user): OptionalUser,
+    jar: CookieJar,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> Result<(CookieJar, Json<LoginResponse>), ApiError> {
+    let code = verify_callback(&jar, query)?;
+    let config = GoogleOAuthConfig::from_env()?;
+    let id_token =
+        google_login_service::exchange_code(&reqwest::Client::new(), &config, &code).await?;
+    let response = google_login_service::login(&conns, &keys, &config, &id_token, user).await?;
+
+    Ok((
+        jar.remove(Cookie::build(STATE_COOKIE).path("/auth"))
+            .add(session_cookie(&response)?),
+        Json(response),
+    ))
+}
+
+/// Signs in with an ID token obtained in the browser, signed-in users get it linked instead.
+#[utoipa::path(
+    post,
+    tag = "Auth",
+    path = "/auth/google",
+    operation_id = "googleIdTokenLogin",
+    security((), ("bearer" = [])),
+    request_body = GoogleIdTokenRequest,
+    responses(
+        (status = OK, body = LoginResponse),
+        (status = UNAUTHORIZED),
+        (status = CONFLICT, description = "Google account is linked to another user"),
+    ),
+)]
+pub async fn google_id_token(
+    Extension(conns): Extension<Connections>,
+    Extension(keys): Extension<Arc<GoogleKeys>>,
+    OptionalUser(user): OptionalUser,
+    jar: CookieJar,
+    Json(request): Json<GoogleIdTokenRequest>,
+) -> Result<(CookieJar, Json<LoginResponse>), ApiError> {
+    let config = GoogleOAuthConfig::from_env()?;
+    let response =
+        google_login_service::login(&conns, &keys, &config, &request.id_token, user).await?;
+
+    Ok((jar.add(session_cookie(&response)?), Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Auth",
+    path = "/auth/me",
+    operation_id = "currentUser",
+    security(("bearer" = [])),
+    responses(
+        (status = OK, body = UserProfile),
+        (status = UNAUTHORIZED),
+    ),
+)]
+pub async fn me(CurrentUser(user): CurrentUser) -> Json<UserProfile> {
+    Json(user.into())
+}
+
+/// Signs out the session used for the request.
+#[utoipa::path(
+    delete,
+    tag = "Auth",
+    path = "/auth/session",
+    operation_id = "logout",
+    security(("bearer" = [])),
+    responses(
+        (status = NO_CONTENT),
+        (status = UNAUTHORIZED),
+    ),
+)]
+pub async fn logout(
+    Extension(conns): Extension<Connections>,
+    CurrentSession { session, .. }: CurrentSession,
+    jar: CookieJar,
+) -> Result<(CookieJar, StatusCode), ApiError> {
+    session_service::revoke(&conns, session.id).await?;
+
+    Ok((
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/")),
+        StatusCode::NO_CONTENT,
+    ))
+}
+
+/// Signs out every session of the current user, e.g. after a token leaked.
+#[utoipa::path(
+    delete,
+    tag = "Auth",
+    path = "/auth/sessions",
+    operation_id = "logoutEverywhere",
+    security(("bearer" = [])),
+    responses(
+        (status = NO_CONTENT),
+        (status = UNAUTHORIZED),
+    ),
+)]
+pub async fn logout_everywhere(
+    Extension(conns): Extension<Connections>,
+    CurrentUser(user): CurrentUser,
+    jar: CookieJar,
+) -> Result<(CookieJar, StatusCode), ApiError> {
+    session_service::revoke_all(&conns, user.id).await?;
+
+    Ok((
+        jar.remove(Cookie::build(SESSION_COOKIE).path("/")),
+        StatusCode::NO_CONTENT,
+    ))
+}
+
+fn new_state() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 16]>())
+}
+
+fn state_cookie(state: String) -> Cookie<'static> {
+    Cookie::build((STATE_COOKIE, state))
+        .path("/auth")
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(Duration::minutes(10))
+        .build()
+}
+
+/// Lets browsers use the session without handling the token themselves.
+fn session_cookie(response: &LoginResponse) -> Result<Cookie<'static>, ApiError> {
+    Ok(Cookie::build((
+        SESSION_COOKIE,
+        session_service::cookie_value(&response.token)?,
+    ))
+    .path("/")
+    .http_only(true)
+    .secure(true)
+    .same_site(SameSite::Lax)
+    .max_age(Duration::seconds(session_service::ttl().num_seconds()))
+    .build())
+}
+
+/// Returns the authorization code once the callback is known to belong to our redirect.
+fn verify_callback(jar: &CookieJar, query: OAuthCallbackQuery) -> Result<String, ApiError> {
+    if let Some(error) = query.error {
+        return Err(ApiError::unauthorized(anyhow!(
+            "login was not approved: {error}"
+        )));
+    }
+
+    let expected = jar.get(STATE_COOKIE).map(Cookie::value);
+    if expected.is_none() || expected != query.state.as_deref() {
+        return Err(ApiError::bad_request(anyhow!("invalid OAuth state")));
+    }
+
+    query
+        .code
+        .ok_or_else(|| ApiError::bad_request(anyhow!("missing authorization code")))
+}
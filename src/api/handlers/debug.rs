@@ -1,17 +1,76 @@
-use axum::{Json, http::StatusCode, response::IntoResponse};
-use tracing::error;
+use axum::{
+    Extension, Json,
+    extract::Path,
+    http::{StatusCode, header},
+};
+use std::sync::Arc;
 
+use crate::Connections;
+use crate::api::auth::CurrentAdmin;
 use crate::error::ApiError;
+use crate::services::admin::audit_service;
+use crate::services::events::crawler::jobs::{self, CrawlJob, CrawlJobs};
+use crate::services::events::crawler::source::SourceConfig;
 
-use crate::services::events::crawler;
-pub async fn crawler() -> Result<impl IntoResponse, ApiError> {
-    crawler::call().await.map_err(|err| {
-        error!(error = %err, "crawler failed");
-        ApiError {
-            status_code: StatusCode::INTERNAL_SERVER_ERROR,
-            error: err,
-        }
-    })?;
+/// Starts the crawler in the background on the CSV or HTTP source given in the body,
+/// `events.csv` in the data directory by default. While a crawl of the same source is running,
+/// also one started by the scheduler, it is returned instead, a crawl of another source is a
+/// conflict.
+pub async fn crawler(
+    Extension(conns): Extension<Connections>,
+    Extension(crawl_jobs): Extension<Arc<CrawlJobs>>,
+    CurrentAdmin(admin): CurrentAdmin,
+    source: Option<Json<SourceConfig>>,
+) -> Result<
+    (
+        StatusCode,
+        [(header::HeaderName, String); 1],
+        Json<CrawlJob>,
+    ),
+    ApiError,
+> {
+    let Json(source) = source.unwrap_or_default();
+    let (job, started) = crawl_jobs.start(&conns, &source).await?;
+    audit_service::record(
+        &conns,
+        &admin,
+        "crawler.start",
+        serde_json::json!({
+            "job_id": job.id,
+            "source": source,
+            "attached": !started,
+        }),
+    )
+    .await;
 
-    Ok(Json(serde_json::json!({ "message": "crawler finished" })))
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/debug/crawler/jobs/{}", job.id))],
+        Json(job),
+    ))
+}
+
+pub async fn crawler_job(
+    Extension(conns): Extension<Connections>,
+    _admin: CurrentAdmin,
+    Path(id): Path<i32>,
+) -> Result<Json<CrawlJob>, ApiError> {
+    Ok(Json(jobs::get(&conns, id).await?))
+}
+
+pub async fn cancel_crawler_job(
+    Extension(conns): Extension<Connections>,
+    CurrentAdmin(admin): CurrentAdmin,
+    Path(id): Path<i32>,
+) -> Result<(StatusCode, Json<CrawlJob>), ApiError> {
+    let job = jobs::cancel(&conns, id).await?;
+    audit_service::record(
+        &conns,
+        &admin,
+        "crawler.cancel",
+        serde_json::json!({ "job_id": job.id }),
+    )
+    .await;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
 }
@@ -1,7 +1,21 @@
-use axum::{Extension, Json};
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{Path, Query},
+    http::header,
+    response::IntoResponse,
+};
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use tracing::error;
+use utoipa::IntoParams;
+use uuid::Uuid;
 
 use crate::Connections;
 use crate::error::ApiError;
+use crate::services::events::details_service::{self, EventDetails};
+use crate::services::events::export_service::{self, EventsExportRequest, ExportFormat};
+use crate::services::events::facets_service::{self, EventsFacetsRequest, EventsFacetsResponse};
 use crate::services::events::search_service::{self, EventsSearchRequest, EventsSearchResponse};
 
 #[utoipa::path(
@@ -20,3 +34,83 @@ pub async fn search(
 ) -> Result<Json<EventsSearchResponse>, ApiError> {
     Ok(Json(search_service::search(&conns, request).await?))
 }
+
+#[utoipa::path(
+    post,
+    tag = "Events",
+    path = "/events/facets",
+    operation_id = "facets",
+    request_body = EventsFacetsRequest,
+    responses(
+        (status = OK, body = EventsFacetsResponse),
+    ),
+)]
+pub async fn facets(
+    Extension(conns): Extension<Connections>,
+    Json(request): Json<EventsFacetsRequest>,
+) -> Result<Json<EventsFacetsResponse>, ApiError> {
+    Ok(Json(facets_service::facets(&conns, request).await?))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Defaults to `csv`.
+    pub format: Option<ExportFormat>,
+}
+
+#[utoipa::path(
+    post,
+    tag = "Events",
+    path = "/events/export",
+    operation_id = "export",
+    params(ExportQuery),
+    request_body = EventsExportRequest,
+    responses(
+        (status = OK, content_type = "text/csv", body = String),
+        (status = OK, content_type = "application/x-ndjson", body = String),
+    ),
+)]
+pub async fn export(
+    Extension(conns): Extension<Connections>,
+    Query(query): Query<ExportQuery>,
+    Json(request): Json<EventsExportRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let format = query.format.unwrap_or_default();
+    let stream = export_service::export(&conns, request, format)?.inspect_err(|err| {
+        error!(error = %err, "event export failed");
+    });
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"events.{}\"",
+                    format.file_extension()
+                ),
+            ),
+        ],
+        Body::from_stream(stream.map_err(|err| err.into_boxed_dyn_error())),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Events",
+    path = "/events/{guid}",
+    operation_id = "show",
+    params(
+        ("guid" = Uuid, Path, description = "Public event GUID"),
+    ),
+    responses(
+        (status = OK, body = EventDetails),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn show(
+    Extension(conns): Extension<Connections>,
+    Path(guid): Path<Uuid>,
+) -> Result<Json<EventDetails>, ApiError> {
+    Ok(Json(details_service::find(&conns, guid).await?))
+}
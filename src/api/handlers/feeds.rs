@@ -0,0 +1,93 @@
+use axum::{Extension, Json, extract::Query, http::header, response::IntoResponse};
+
+use crate::Connections;
+use crate::error::ApiError;
+use crate::services::events::calendar_service;
+use crate::services::events::feed_filters::{self, FeedQuery, FeedToken};
+use crate::services::events::news_feed_service;
+use crate::services::events::search_service::EventsSearchFilters;
+
+#[utoipa::path(
+    post,
+    tag = "Feeds",
+    path = "/events/feeds/token",
+    operation_id = "feedToken",
+    request_body = EventsSearchFilters,
+    responses(
+        (status = OK, body = FeedToken),
+    ),
+)]
+pub async fn token(Json(filters): Json<EventsSearchFilters>) -> Json<FeedToken> {
+    Json(FeedToken {
+        token: feed_filters::encode_token(&filters),
+    })
+}
+
+#[utoipa::path(
+    get,
+    tag = "Feeds",
+    path = "/events/calendar.ics",
+    operation_id = "calendar",
+    params(FeedQuery),
+    responses(
+        (status = OK, content_type = "text/calendar", body = String),
+    ),
+)]
+pub async fn calendar(
+    Extension(conns): Extension<Connections>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filters = query.into_filters().map_err(ApiError::bad_request)?;
+    let body = calendar_service::calendar(&conns, filters).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Feeds",
+    path = "/events/feeds/atom.xml",
+    operation_id = "atomFeed",
+    params(FeedQuery),
+    responses(
+        (status = OK, content_type = "application/atom+xml", body = String),
+    ),
+)]
+pub async fn atom(
+    Extension(conns): Extension<Connections>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filters = query.into_filters().map_err(ApiError::bad_request)?;
+    let body = news_feed_service::atom(&conns, filters).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        body,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Feeds",
+    path = "/events/feeds/rss.xml",
+    operation_id = "rssFeed",
+    params(FeedQuery),
+    responses(
+        (status = OK, content_type = "application/rss+xml", body = String),
+    ),
+)]
+pub async fn rss(
+    Extension(conns): Extension<Connections>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filters = query.into_filters().map_err(ApiError::bad_request)?;
+    let body = news_feed_service::rss(&conns, filters).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        body,
+    ))
+}
@@ -1,2 +1,7 @@
+pub mod admin;
+pub mod auth;
 pub mod debug;
 pub mod events;
+pub mod feeds;
+pub mod organizers;
+pub mod subscriptions;
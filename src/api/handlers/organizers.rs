@@ -0,0 +1,89 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query},
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::Connections;
+use crate::error::ApiError;
+use crate::services::events::search_service::{EventState, EventsSearchResponse};
+use crate::services::organizers::search_service::{
+    self, OrganizerFull, OrganizersSearchRequest, OrganizersSearchResponse,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OrganizerEventsQuery {
+    /// Defaults to `upcoming`.
+    pub state: Option<EventState>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+#[utoipa::path(
+    post,
+    tag = "Organizers",
+    path = "/organizers/search",
+    operation_id = "searchOrganizers",
+    request_body = OrganizersSearchRequest,
+    responses(
+        (status = OK, body = OrganizersSearchResponse),
+    ),
+)]
+pub async fn search(
+    Extension(conns): Extension<Connections>,
+    Json(request): Json<OrganizersSearchRequest>,
+) -> Result<Json<OrganizersSearchResponse>, ApiError> {
+    Ok(Json(search_service::search(&conns, request).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Organizers",
+    path = "/organizers/{id}",
+    operation_id = "showOrganizer",
+    params(
+        ("id" = i32, Path, description = "Organizer ID"),
+    ),
+    responses(
+        (status = OK, body = OrganizerFull),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn show(
+    Extension(conns): Extension<Connections>,
+    Path(id): Path<i32>,
+) -> Result<Json<OrganizerFull>, ApiError> {
+    Ok(Json(search_service::find(&conns, id).await?))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Organizers",
+    path = "/organizers/{id}/events",
+    operation_id = "organizerEvents",
+    params(
+        ("id" = i32, Path, description = "Organizer ID"),
+        OrganizerEventsQuery,
+    ),
+    responses(
+        (status = OK, body = EventsSearchResponse),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn events(
+    Extension(conns): Extension<Connections>,
+    Path(id): Path<i32>,
+    Query(query): Query<OrganizerEventsQuery>,
+) -> Result<Json<EventsSearchResponse>, ApiError> {
+    Ok(Json(
+        search_service::events(
+            &conns,
+            id,
+            query.state.unwrap_or(EventState::Upcoming),
+            query.page.unwrap_or(1),
+            query.page_size.unwrap_or(20),
+        )
+        .await?,
+    ))
+}
@@ -0,0 +1,181 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query},
+    http::StatusCode,
+    response::Html,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::Connections;
+use crate::api::auth::CurrentUser;
+use crate::error::ApiError;
+use crate::services::subscriptions::management_service::{self, Subscription, SubscriptionRequest};
+use crate::services::subscriptions::unsubscribe_service;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UnsubscribeQuery {
+    /// Signature from the unsubscribe link of a notification email.
+    pub token: String,
+}
+
+#[utoipa::path(
+    get,
+    tag = "Subscriptions",
+    path = "/subscriptions",
+    operation_id = "listSubscriptions",
+    security(("bearer" = [])),
+    responses(
+        (status = OK, body = Vec<Subscription>),
+        (status = UNAUTHORIZED),
+    ),
+)]
+pub async fn list(
+    Extension(conns): Extension<Connections>,
+    CurrentUser(user): CurrentUser,
+) -> Result<Json<Vec<Subscription>>, ApiError> {
+    Ok(Json(management_service::list(&conns, &user).await?))
+}
+
+#[utoipa::path(
+    post,
+    tag = "Subscriptions",
+    path = "/subscriptions",
+    operation_id = "createSubscription",
+    security(("bearer" = [])),
+    request_body = SubscriptionRequest,
+    responses(
+        (status = CREATED, body = Subscription),
+        (status = BAD_REQUEST),
+        (status = UNAUTHORIZED),
+        (status = UNPROCESSABLE_ENTITY),
+    ),
+)]
+pub async fn create(
+    Extension(conns): Extension<Connections>,
+    CurrentUser(user): CurrentUser,
+    Json(request): Json<SubscriptionRequest>,
+) -> Result<(StatusCode, Json<Subscription>), ApiError> {
+    Ok((
+        StatusCode::CREATED,
+        Json(management_service::create(&conns, &user, request).await?),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    tag = "Subscriptions",
+    path = "/subscriptions/{id}",
+    operation_id = "updateSubscription",
+    security(("bearer" = [])),
+    params(
+        ("id" = i32, Path, description = "Subscription ID"),
+    ),
+    request_body = SubscriptionRequest,
+    responses(
+        (status = OK, body = Subscription),
+        (status = BAD_REQUEST),
+        (status = UNAUTHORIZED),
+        (status = UNPROCESSABLE_ENTITY),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn update(
+    Extension(conns): Extension<Connections>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+    Json(request): Json<SubscriptionRequest>,
+) -> Result<Json<Subscription>, ApiError> {
+    Ok(Json(
+        management_service::update(&conns, &user, id, request).await?,
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Subscriptions",
+    path = "/subscriptions/{id}",
+    operation_id = "deleteSubscription",
+    security(("bearer" = [])),
+    params(
+        ("id" = i32, Path, description = "Subscription ID"),
+    ),
+    responses(
+        (status = NO_CONTENT),
+        (status = UNAUTHORIZED),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn delete(
+    Extension(conns): Extension<Connections>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, ApiError> {
+    management_service::delete(&conns, &user, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Target of the unsubscribe link in notification emails.
+///
+/// Only asks for confirmation, mail scanners and link prefetchers fetch links without a user
+/// clicking them.
+#[utoipa::path(
+    get,
+    tag = "Subscriptions",
+    path = "/subscriptions/{id}/unsubscribe",
+    operation_id = "confirmUnsubscribe",
+    params(
+        ("id" = i32, Path, description = "Subscription ID"),
+        UnsubscribeQuery,
+    ),
+    responses(
+        (status = OK, content_type = "text/html", body = String),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn confirm_unsubscribe(
+    Path(id): Path<i32>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Html<&'static str>, ApiError> {
+    unsubscribe_service::verify(id, &query.token)?;
+
+    // Without an action the form posts to this URL, token included.
+    Ok(Html(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Unsubscribe</title></head>
+<body>
+<p>Stop notifications for this subscription?</p>
+<form method="post"><button type="submit">Unsubscribe</button></form>
+</body>
+</html>
+"#,
+    ))
+}
+
+/// Disables the subscription, submitted by the confirmation page or as an RFC 8058 one-click
+/// unsubscribe.
+#[utoipa::path(
+    post,
+    tag = "Subscriptions",
+    path = "/subscriptions/{id}/unsubscribe",
+    operation_id = "unsubscribe",
+    params(
+        ("id" = i32, Path, description = "Subscription ID"),
+        UnsubscribeQuery,
+    ),
+    responses(
+        (status = OK, content_type = "text/plain", body = String),
+        (status = NOT_FOUND),
+    ),
+)]
+pub async fn unsubscribe(
+    Extension(conns): Extension<Connections>,
+    Path(id): Path<i32>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<&'static str, ApiError> {
+    unsubscribe_service::unsubscribe(&conns, id, &query.token).await?;
+
+    Ok("You have been unsubscribed, no more notifications will be sent for this subscription.")
+}
@@ -1,16 +1,62 @@
-use utoipa::OpenApi;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
+        crate::api::handlers::admin::crawl_runs,
+        crate::api::handlers::admin::last_crawl_run,
+        crate::api::handlers::admin::crawl_run,
+        crate::api::handlers::admin::crawl_rejections,
+        crate::api::handlers::auth::discord_login,
+        crate::api::handlers::auth::discord_callback,
+        crate::api::handlers::auth::google_login,
+        crate::api::handlers::auth::google_callback,
+        crate::api::handlers::auth::google_id_token,
+        crate::api::handlers::auth::me,
+        crate::api::handlers::auth::logout,
+        crate::api::handlers::auth::logout_everywhere,
         crate::api::handlers::events::search,
+        crate::api::handlers::events::facets,
+        crate::api::handlers::events::export,
+        crate::api::handlers::events::show,
+        crate::api::handlers::feeds::token,
+        crate::api::handlers::feeds::calendar,
+        crate::api::handlers::feeds::atom,
+        crate::api::handlers::feeds::rss,
+        crate::api::handlers::organizers::search,
+        crate::api::handlers::organizers::show,
+        crate::api::handlers::organizers::events,
+        crate::api::handlers::subscriptions::list,
+        crate::api::handlers::subscriptions::create,
+        crate::api::handlers::subscriptions::update,
+        crate::api::handlers::subscriptions::delete,
+        crate::api::handlers::subscriptions::confirm_unsubscribe,
+        crate::api::handlers::subscriptions::unsubscribe,
     ),
     components(schemas(
 
     )),
+    modifiers(&SecurityAddon),
     servers(
         (url = "http://localhost:4400/"),
         (url = "https://poketcgevents-api.onrender.com/"),
     )
 )]
 pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+    }
+}
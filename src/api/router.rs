@@ -1,28 +1,86 @@
 use anyhow::Context;
 use axum::{
-    Extension, Router,
-    routing::{get, post},
+    Extension, Router, middleware,
+    routing::{delete, get, post, put},
 };
 use std::net::SocketAddr;
+use std::sync::Arc;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+use super::auth;
 use super::handlers;
 use super::openapi::ApiDoc;
-use crate::connections;
-
-pub async fn call() -> Result<(), anyhow::Error> {
-    let conns = connections::build().await?;
+use crate::Connections;
+use crate::services::events::crawler::jobs::CrawlJobs;
+use crate::services::users::google_login_service::GoogleKeys;
 
+pub async fn call(conns: Connections, crawl_jobs: Arc<CrawlJobs>) -> Result<(), anyhow::Error> {
     let openapi_config = utoipa_swagger_ui::Config::default()
         .display_operation_id(true)
         .display_request_duration(true);
 
     let app = Router::new()
         .route("/", get(root))
+        .route("/auth/discord", get(handlers::auth::discord_login))
+        .route(
+            "/auth/discord/callback",
+            get(handlers::auth::discord_callback),
+        )
+        .route(
+            "/auth/google",
+            get(handlers::auth::google_login).post(handlers::auth::google_id_token),
+        )
+        .route(
+            "/auth/google/callback",
+            get(handlers::auth::google_callback),
+        )
+        .route("/admin/crawl-runs", get(handlers::admin::crawl_runs))
+        .route(
+            "/admin/crawl-runs/last",
+            get(handlers::admin::last_crawl_run),
+        )
+        .route("/admin/crawl-runs/{id}", get(handlers::admin::crawl_run))
+        .route(
+            "/admin/crawl-runs/{id}/rejections",
+            get(handlers::admin::crawl_rejections),
+        )
+        .route("/auth/me", get(handlers::auth::me))
+        .route("/auth/session", delete(handlers::auth::logout))
+        .route("/auth/sessions", delete(handlers::auth::logout_everywhere))
         .route("/events/search", post(handlers::events::search))
+        .route("/events/facets", post(handlers::events::facets))
+        .route("/events/export", post(handlers::events::export))
+        .route("/events/feeds/token", post(handlers::feeds::token))
+        .route("/events/calendar.ics", get(handlers::feeds::calendar))
+        .route("/events/feeds/atom.xml", get(handlers::feeds::atom))
+        .route("/events/feeds/rss.xml", get(handlers::feeds::rss))
+        .route("/events/{guid}", get(handlers::events::show))
+        .route("/organizers/search", post(handlers::organizers::search))
+        .route("/organizers/{id}", get(handlers::organizers::show))
+        .route("/organizers/{id}/events", get(handlers::organizers::events))
+        .route(
+            "/subscriptions",
+            get(handlers::subscriptions::list).post(handlers::subscriptions::create),
+        )
+        .route(
+            "/subscriptions/{id}",
+            put(handlers::subscriptions::update).delete(handlers::subscriptions::delete),
+        )
+        .route(
+            "/subscriptions/{id}/unsubscribe",
+            get(handlers::subscriptions::confirm_unsubscribe)
+                .post(handlers::subscriptions::unsubscribe),
+        )
         .route("/debug/crawler", post(handlers::debug::crawler))
+        .route(
+            "/debug/crawler/jobs/{id}",
+            get(handlers::debug::crawler_job).delete(handlers::debug::cancel_crawler_job),
+        )
+        .layer(middleware::from_fn(auth::authenticate))
         .layer(Extension(conns))
+        .layer(Extension(Arc::new(GoogleKeys::from_env())))
+        .layer(Extension(crawl_jobs))
         .merge(
             SwaggerUi::new("/swagger-ui")
                 .url("/api-docs/openapi.json", ApiDoc::openapi())
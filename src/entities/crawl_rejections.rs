@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "crawl_rejections")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub crawl_run_id: i32,
+    pub record_number: i32,
+    #[sea_orm(column_type = "Text")]
+    pub reason: String,
+    #[sea_orm(column_type = "Text")]
+    pub error: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub raw: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    #[sea_orm(
+        belongs_to,
+        from = "crawl_run_id",
+        to = "id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    pub crawl_run: HasOne<super::crawl_runs::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
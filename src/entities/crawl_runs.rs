@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[sea_orm::model]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "crawl_runs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub source: String,
+    pub started_at: DateTimeWithTimeZone,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+    pub rows_read: i32,
+    pub rows_skipped: i32,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub skipped_by_reason: Json,
+    pub organizers_created: i32,
+    pub events_inserted: i32,
+    pub events_updated: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub trigger: Option<String>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub source_config: Option<Json>,
+    pub cancel_requested_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(has_many)]
+    pub crawl_rejections: HasMany<super::crawl_rejections::Entity>,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -74,9 +74,9 @@ pub enum EventKind {
     Other(String),
 }
 
-impl Into<Value> for EventKind {
-    fn into(self) -> Value {
-        self.to_value().into()
+impl From<EventKind> for Value {
+    fn from(kind: EventKind) -> Self {
+        kind.to_value().into()
     }
 }
 
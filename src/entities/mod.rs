@@ -1,7 +1,12 @@
+pub mod audit_logs;
+pub mod crawl_rejections;
+pub mod crawl_runs;
 pub mod discord_users;
 pub mod events;
 pub mod google_users;
 pub mod organizers;
+pub mod scheduled_jobs;
+pub mod user_sessions;
 pub mod user_subscription_notifications;
 pub mod user_subscriptions;
 pub mod users;
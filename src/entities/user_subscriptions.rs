@@ -15,6 +15,10 @@ pub struct Model {
     pub notify_before: i32,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub disabled_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub disabled_reason: Option<String>,
+    pub last_notified_at: Option<DateTimeWithTimeZone>,
     #[sea_orm(has_many)]
     pub user_subscription_notifications: HasMany<super::user_subscription_notifications::Entity>,
     #[sea_orm(
@@ -15,6 +15,10 @@ pub struct Model {
     pub google_id: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    #[sea_orm(column_type = "Text")]
+    pub role: String,
+    #[sea_orm(has_many)]
+    pub audit_logs: HasMany<super::audit_logs::Entity>,
     #[sea_orm(
         belongs_to,
         from = "discord_id",
@@ -32,6 +36,8 @@ pub struct Model {
     )]
     pub google_user: HasOne<super::google_users::Entity>,
     #[sea_orm(has_many)]
+    pub user_sessions: HasMany<super::user_sessions::Entity>,
+    #[sea_orm(has_many)]
     pub user_subscriptions: HasMany<super::user_subscriptions::Entity>,
 }
 
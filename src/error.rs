@@ -7,6 +7,50 @@ pub struct ApiError {
     pub error: anyhow::Error,
 }
 
+impl ApiError {
+    pub fn bad_request(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::BAD_REQUEST,
+            error,
+        }
+    }
+
+    pub fn unauthorized(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::UNAUTHORIZED,
+            error,
+        }
+    }
+
+    pub fn forbidden(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::FORBIDDEN,
+            error,
+        }
+    }
+
+    pub fn not_found(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::NOT_FOUND,
+            error,
+        }
+    }
+
+    pub fn conflict(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::CONFLICT,
+            error,
+        }
+    }
+
+    pub fn unprocessable_entity(error: anyhow::Error) -> Self {
+        ApiError {
+            status_code: StatusCode::UNPROCESSABLE_ENTITY,
+            error,
+        }
+    }
+}
+
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -8,10 +8,27 @@ mod services;
 
 pub use connections::Connections;
 
+use std::sync::Arc;
+
+use services::events::crawler::{jobs::CrawlJobs, scheduler};
+use services::notifications::{delivery::ChannelDelivery, dispatcher};
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     dotenvy::dotenv().ok();
     connections::migrate().await?;
     logging::setup();
-    api::router::call().await
+
+    let conns = connections::build().await?;
+    dispatcher::spawn(
+        conns.clone(),
+        Arc::new(ChannelDelivery::from_env(&conns)?),
+        dispatcher::DispatcherConfig::from_env(),
+    );
+    let crawl_jobs = Arc::new(CrawlJobs::default());
+    if let Some(config) = scheduler::SchedulerConfig::from_env()? {
+        scheduler::spawn(conns.clone(), crawl_jobs.clone(), config);
+    }
+
+    api::router::call(conns, crawl_jobs).await
 }
@@ -0,0 +1,10 @@
+use sea_orm::*;
+
+use crate::entities::audit_logs;
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    model: audit_logs::ActiveModel,
+) -> Result<audit_logs::Model, anyhow::Error> {
+    model.insert(db).await.map_err(anyhow::Error::from)
+}
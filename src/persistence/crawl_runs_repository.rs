@@ -0,0 +1,117 @@
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::entities::{crawl_rejections, crawl_runs};
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    model: crawl_runs::ActiveModel,
+) -> Result<crawl_runs::Model, anyhow::Error> {
+    model.insert(db).await.map_err(anyhow::Error::from)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    model: crawl_runs::ActiveModel,
+) -> Result<crawl_runs::Model, anyhow::Error> {
+    model.update(db).await.map_err(anyhow::Error::from)
+}
+
+pub async fn find_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<crawl_runs::Model>, anyhow::Error> {
+    crawl_runs::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+/// Most recent runs first.
+pub async fn latest(
+    db: &DatabaseConnection,
+    limit: u64,
+) -> Result<Vec<crawl_runs::Model>, anyhow::Error> {
+    crawl_runs::Entity::find()
+        .order_by(crawl_runs::Column::StartedAt, Order::Desc)
+        .order_by(crawl_runs::Column::Id, Order::Desc)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+/// The most recent run that has not finished yet.
+pub async fn running(db: &DatabaseConnection) -> Result<Option<crawl_runs::Model>, anyhow::Error> {
+    crawl_runs::Entity::find()
+        .filter(crawl_runs::Column::FinishedAt.is_null())
+        .order_by(crawl_runs::Column::StartedAt, Order::Desc)
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+/// Ends the unfinished run `id`, or every unfinished run when `id` is `None`, with `error`.
+/// Returns the number of runs ended.
+pub async fn end_running(
+    db: &DatabaseConnection,
+    id: Option<i32>,
+    error: &str,
+) -> Result<u64, anyhow::Error> {
+    let mut query = crawl_runs::Entity::update_many()
+        .col_expr(crawl_runs::Column::FinishedAt, Expr::current_timestamp())
+        .col_expr(crawl_runs::Column::Error, Expr::value(error))
+        .filter(crawl_runs::Column::FinishedAt.is_null());
+    if let Some(id) = id {
+        query = query.filter(crawl_runs::Column::Id.eq(id));
+    }
+
+    Ok(query.exec(db).await?.rows_affected)
+}
+
+/// Flags the run for cancellation unless it already finished, returns whether it was flagged.
+pub async fn request_cancel(db: &DatabaseConnection, id: i32) -> Result<bool, anyhow::Error> {
+    let result = crawl_runs::Entity::update_many()
+        .col_expr(
+            crawl_runs::Column::CancelRequestedAt,
+            Expr::current_timestamp(),
+        )
+        .filter(crawl_runs::Column::Id.eq(id))
+        .filter(crawl_runs::Column::FinishedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+pub async fn insert_rejection(
+    db: &DatabaseConnection,
+    model: crawl_rejections::ActiveModel,
+) -> Result<(), anyhow::Error> {
+    crawl_rejections::Entity::insert(model)
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Rejections of the run in the order they were read, starting after `after_id`.
+pub async fn rejections(
+    db: &DatabaseConnection,
+    crawl_run_id: i32,
+    after_id: Option<i32>,
+    limit: u64,
+) -> Result<Vec<crawl_rejections::Model>, anyhow::Error> {
+    let mut query = crawl_rejections::Entity::find()
+        .filter(crawl_rejections::Column::CrawlRunId.eq(crawl_run_id));
+    if let Some(after_id) = after_id {
+        query = query.filter(crawl_rejections::Column::Id.gt(after_id));
+    }
+
+    query
+        .order_by(crawl_rejections::Column::Id, Order::Asc)
+        .limit(limit)
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
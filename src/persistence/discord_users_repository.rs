@@ -0,0 +1,24 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+
+use crate::entities::discord_users;
+
+/// Inserts the Discord account or refreshes its profile when it is already known.
+pub async fn upsert(
+    db: &DatabaseConnection,
+    model: discord_users::ActiveModel,
+) -> Result<discord_users::Model, anyhow::Error> {
+    discord_users::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(discord_users::Column::DiscordId)
+                .update_columns([
+                    discord_users::Column::Nickname,
+                    discord_users::Column::AvatarUrl,
+                    discord_users::Column::IsVerified,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
@@ -1,12 +1,62 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::sea_query::Expr;
 use sea_orm::*;
-use sea_query::OnConflict;
+use sea_query::{OnConflict, Query};
+use std::collections::HashSet;
+use uuid::Uuid;
 
-use crate::entities::events;
+use crate::entities::events::EventKind;
+use crate::entities::{events, organizers};
 
+#[derive(Debug, FromQueryResult)]
+pub struct OrganizerKindStats {
+    pub organizer_id: i32,
+    pub kind: String,
+    pub total_events: i64,
+    pub next_event_at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct LocationCount {
+    pub country: String,
+    pub area: String,
+    pub city: String,
+    pub total_events: i64,
+}
+
+pub async fn find_by_guid(
+    db: &DatabaseConnection,
+    guid: Uuid,
+) -> Result<Option<events::Model>, anyhow::Error> {
+    events::Entity::find()
+        .filter(events::Column::Guid.eq(guid))
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+/// Inserted and updated events of an [`upsert`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UpsertCounts {
+    pub inserted: u64,
+    pub updated: u64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct UpsertedRow {
+    inserted: bool,
+}
+
+/// Inserts the events or updates those with a known guid, counting both as reported by Postgres.
 pub async fn upsert(
     db: &DatabaseConnection,
     models: Vec<events::ActiveModel>,
-) -> Result<(), anyhow::Error> {
+) -> Result<UpsertCounts, anyhow::Error> {
+    let models = dedup_by_guid(models);
+    if models.is_empty() {
+        return Ok(UpsertCounts::default());
+    }
+
     let on_conflict = OnConflict::columns([events::Column::Guid])
         .update_columns(vec![
             events::Column::Name,
@@ -17,15 +67,121 @@ pub async fn upsert(
             events::Column::UpdatedAt,
         ])
         .to_owned();
+    let mut insert = events::Entity::insert_many(models).on_conflict(on_conflict);
+    // `xmax` is only set on rows that were updated rather than inserted.
+    QueryTrait::query(&mut insert)
+        .returning(Query::returning().expr(Expr::cust(r#"("events"."xmax" = 0) AS "inserted""#)));
+
+    let rows = UpsertedRow::find_by_statement(insert.build(db.get_database_backend()))
+        .all(db)
+        .await?;
+    let inserted = rows.iter().filter(|row| row.inserted).count() as u64;
+
+    Ok(UpsertCounts {
+        inserted,
+        updated: rows.len() as u64 - inserted,
+    })
+}
+
+/// Postgres cannot update a row twice in one statement, so only the last model of a guid is kept.
+fn dedup_by_guid(models: Vec<events::ActiveModel>) -> Vec<events::ActiveModel> {
+    let mut guids = HashSet::new();
+    let mut models = models
+        .into_iter()
+        .rev()
+        .filter(|model| {
+            model
+                .guid
+                .try_as_ref()
+                .is_none_or(|guid| guids.insert(*guid))
+        })
+        .collect::<Vec<_>>();
+    models.reverse();
+
+    models
+}
+
+/// Event counts and the next upcoming event per organizer and kind.
+pub async fn organizer_kind_stats(
+    db: &DatabaseConnection,
+    organizer_ids: Vec<i32>,
+    upcoming_cutoff: DateTime<Utc>,
+) -> Result<Vec<OrganizerKindStats>, anyhow::Error> {
+    events::Entity::find()
+        .select_only()
+        .column(events::Column::OrganizerId)
+        .column(events::Column::Kind)
+        .column_as(Expr::col(events::Column::Id).count(), "total_events")
+        .column_as(
+            Expr::cust_with_values(
+                r#"MIN("events"."happening_at") FILTER (WHERE "events"."happening_at" > $1)"#,
+                [upcoming_cutoff],
+            ),
+            "next_event_at",
+        )
+        .filter(events::Column::OrganizerId.is_in(organizer_ids))
+        .group_by(events::Column::OrganizerId)
+        .group_by(events::Column::Kind)
+        .into_model::<OrganizerKindStats>()
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+/// Number of events starting after `happening_after` per organizer country, area and city.
+pub async fn location_counts(
+    db: &DatabaseConnection,
+    kinds: Option<Vec<EventKind>>,
+    happening_after: DateTime<Utc>,
+) -> Result<Vec<LocationCount>, anyhow::Error> {
+    events::Entity::find()
+        .join(JoinType::InnerJoin, events::Relation::Organizers.def())
+        .select_only()
+        .column(organizers::Column::Country)
+        .column(organizers::Column::Area)
+        .column(organizers::Column::City)
+        .column_as(
+            Expr::col((events::Entity, events::Column::Id)).count(),
+            "total_events",
+        )
+        .filter(events::Column::HappeningAt.gt(happening_after))
+        .apply_if(kinds, |query, kinds| {
+            query.filter(events::Column::Kind.is_in(kinds))
+        })
+        .group_by(organizers::Column::Country)
+        .group_by(organizers::Column::Area)
+        .group_by(organizers::Column::City)
+        .into_model::<LocationCount>()
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(guid: Uuid, name: &str) -> events::ActiveModel {
+        events::ActiveModel {
+            guid: Set(guid),
+            name: Set(name.to_string()),
+            ..Default::default()
+        }
+    }
 
-    let insert_result = events::Entity::insert_many(models)
-        .on_conflict(on_conflict)
-        .exec(db)
-        .await;
+    #[test]
+    fn test_dedup_by_guid() {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let models = dedup_by_guid(vec![
+            model(first, "old"),
+            model(second, "other"),
+            model(first, "new"),
+        ]);
 
-    match insert_result {
-        Ok(_) => Ok(()),
-        Err(sea_orm::error::DbErr::RecordNotInserted) => Ok(()),
-        Err(e) => Err(anyhow::Error::from(e)),
+        let names = models
+            .iter()
+            .map(|model| model.name.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["other", "new"]);
     }
 }
@@ -0,0 +1,26 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+
+use crate::entities::google_users;
+
+/// Inserts the Google account or refreshes its profile when it is already known.
+pub async fn upsert(
+    db: &DatabaseConnection,
+    model: google_users::ActiveModel,
+) -> Result<google_users::Model, anyhow::Error> {
+    google_users::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(google_users::Column::GoogleId)
+                .update_columns([
+                    google_users::Column::Email,
+                    google_users::Column::EmailVerified,
+                    google_users::Column::FirstName,
+                    google_users::Column::LastName,
+                    google_users::Column::ProfileImageUrl,
+                ])
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
@@ -1,2 +1,11 @@
+pub mod audit_logs_repository;
+pub mod crawl_runs_repository;
+pub mod discord_users_repository;
 pub mod events_repository;
+pub mod google_users_repository;
 pub mod organizers_repository;
+pub mod scheduled_jobs_repository;
+pub mod user_sessions_repository;
+pub mod user_subscription_notifications_repository;
+pub mod user_subscriptions_repository;
+pub mod users_repository;
@@ -3,6 +3,16 @@ use std::collections::HashMap;
 
 use crate::entities::organizers;
 
+pub async fn find_by_id(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<Option<organizers::Model>, anyhow::Error> {
+    organizers::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
 pub async fn all_by_ids(
     db: &DatabaseConnection,
     ids: Vec<i32>,
@@ -0,0 +1,77 @@
+use chrono::{DateTime, FixedOffset};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::sqlx::{self, Connection as _, PgConnection};
+use sea_orm::*;
+
+use crate::entities::scheduled_jobs;
+
+pub async fn last_run_at<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+) -> Result<Option<DateTime<FixedOffset>>, anyhow::Error> {
+    let job = scheduled_jobs::Entity::find_by_id(name.to_string())
+        .one(db)
+        .await?;
+
+    Ok(job.map(|job| job.last_run_at))
+}
+
+pub async fn touch<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+    last_run_at: DateTime<FixedOffset>,
+) -> Result<(), anyhow::Error> {
+    scheduled_jobs::Entity::insert(scheduled_jobs::ActiveModel {
+        name: Set(name.to_string()),
+        last_run_at: Set(last_run_at),
+    })
+    .on_conflict(
+        OnConflict::column(scheduled_jobs::Column::Name)
+            .update_column(scheduled_jobs::Column::LastRunAt)
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+/// A job's session-level Postgres advisory lock, see [`try_lock`].
+pub struct JobLock {
+    conn: PgConnection,
+    name: String,
+}
+
+/// Takes the job's Postgres advisory lock, so that only one replica runs it. Returns `None` when
+/// it is held elsewhere.
+///
+/// The lock lives on a connection detached from the pool instead of in a transaction, which
+/// would sit idle for the whole run. Should [`JobLock::unlock`] not be reached, dropping the
+/// connection releases the lock.
+pub async fn try_lock(
+    db: &DatabaseConnection,
+    name: &str,
+) -> Result<Option<JobLock>, anyhow::Error> {
+    let mut conn = db.get_postgres_connection_pool().acquire().await?.detach();
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1))")
+        .bind(name)
+        .fetch_one(&mut conn)
+        .await?;
+
+    Ok(locked.then(|| JobLock {
+        conn,
+        name: name.to_string(),
+    }))
+}
+
+impl JobLock {
+    pub async fn unlock(mut self) -> Result<(), anyhow::Error> {
+        sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+            .bind(&self.name)
+            .execute(&mut self.conn)
+            .await?;
+        self.conn.close().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::entities::{user_sessions, users};
+
+/// Finds the session with its user, unless it expired or was revoked.
+pub async fn find_active_by_token_hash(
+    db: &DatabaseConnection,
+    token_hash: &str,
+) -> Result<Option<(user_sessions::Model, users::Model)>, anyhow::Error> {
+    let session = user_sessions::Entity::find()
+        .filter(user_sessions::Column::TokenHash.eq(token_hash))
+        .filter(user_sessions::Column::ExpiresAt.gt(Utc::now()))
+        .filter(user_sessions::Column::RevokedAt.is_null())
+        .find_also_related(users::Entity)
+        .one(db)
+        .await?;
+
+    Ok(session.and_then(|(session, user)| user.map(|user| (session, user))))
+}
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    user_id: i32,
+    token_hash: &str,
+    expires_at: DateTime<FixedOffset>,
+) -> Result<user_sessions::Model, anyhow::Error> {
+    user_sessions::ActiveModel {
+        id: Default::default(),
+        user_id: Set(user_id),
+        token_hash: Set(token_hash.to_string()),
+        created_at: Set(Utc::now().fixed_offset()),
+        expires_at: Set(expires_at),
+        revoked_at: Set(None),
+    }
+    .insert(db)
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+pub async fn revoke(db: &DatabaseConnection, id: i32) -> Result<(), anyhow::Error> {
+    user_sessions::Entity::update_many()
+        .col_expr(user_sessions::Column::RevokedAt, Expr::current_timestamp())
+        .filter(user_sessions::Column::Id.eq(id))
+        .filter(user_sessions::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn revoke_all_by_user_id(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<u64, anyhow::Error> {
+    let result = user_sessions::Entity::update_many()
+        .col_expr(user_sessions::Column::RevokedAt, Expr::current_timestamp())
+        .filter(user_sessions::Column::UserId.eq(user_id))
+        .filter(user_sessions::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
@@ -0,0 +1,52 @@
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+
+use crate::entities::user_subscription_notifications;
+
+/// Records that `event_id` is being notified to `user_subscription_id`.
+///
+/// Returns `false` when it was already recorded, so concurrent dispatchers never send twice.
+pub async fn claim(
+    db: &DatabaseConnection,
+    user_subscription_id: i32,
+    event_id: i32,
+) -> Result<bool, anyhow::Error> {
+    let model = user_subscription_notifications::ActiveModel {
+        id: Default::default(),
+        user_subscription_id: Set(user_subscription_id),
+        event_id: Set(event_id),
+        created_at: Set(Utc::now().fixed_offset()),
+    };
+
+    let result = user_subscription_notifications::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([
+                user_subscription_notifications::Column::UserSubscriptionId,
+                user_subscription_notifications::Column::EventId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(result > 0)
+}
+
+/// Forgets a claim whose delivery failed so that it is attempted again later.
+pub async fn release(
+    db: &DatabaseConnection,
+    user_subscription_id: i32,
+    event_id: i32,
+) -> Result<(), anyhow::Error> {
+    user_subscription_notifications::Entity::delete_many()
+        .filter(
+            user_subscription_notifications::Column::UserSubscriptionId.eq(user_subscription_id),
+        )
+        .filter(user_subscription_notifications::Column::EventId.eq(event_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
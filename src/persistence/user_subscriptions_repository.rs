@@ -0,0 +1,100 @@
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::entities::{user_subscription_notifications, user_subscriptions};
+
+pub async fn all_by_user_id(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<user_subscriptions::Model>, anyhow::Error> {
+    user_subscriptions::Entity::find()
+        .filter(user_subscriptions::Column::UserId.eq(user_id))
+        .order_by(user_subscriptions::Column::Id, Order::Asc)
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn find_by_id_and_user_id(
+    db: &DatabaseConnection,
+    id: i32,
+    user_id: i32,
+) -> Result<Option<user_subscriptions::Model>, anyhow::Error> {
+    user_subscriptions::Entity::find_by_id(id)
+        .filter(user_subscriptions::Column::UserId.eq(user_id))
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    model: user_subscriptions::ActiveModel,
+) -> Result<user_subscriptions::Model, anyhow::Error> {
+    model.insert(db).await.map_err(anyhow::Error::from)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    model: user_subscriptions::ActiveModel,
+) -> Result<user_subscriptions::Model, anyhow::Error> {
+    model.update(db).await.map_err(anyhow::Error::from)
+}
+
+/// Deletes the subscription together with its notification history.
+pub async fn delete(db: &DatabaseConnection, id: i32) -> Result<(), anyhow::Error> {
+    let txn = db.begin().await?;
+
+    user_subscription_notifications::Entity::delete_many()
+        .filter(user_subscription_notifications::Column::UserSubscriptionId.eq(id))
+        .exec(&txn)
+        .await?;
+    user_subscriptions::Entity::delete_by_id(id)
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn all_enabled(
+    db: &DatabaseConnection,
+) -> Result<Vec<user_subscriptions::Model>, anyhow::Error> {
+    user_subscriptions::Entity::find()
+        .filter(user_subscriptions::Column::DisabledAt.is_null())
+        .order_by(user_subscriptions::Column::Id, Order::Asc)
+        .all(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn disable(db: &DatabaseConnection, id: i32, reason: &str) -> Result<(), anyhow::Error> {
+    user_subscriptions::Entity::update_many()
+        .col_expr(
+            user_subscriptions::Column::DisabledAt,
+            Expr::current_timestamp(),
+        )
+        .col_expr(
+            user_subscriptions::Column::DisabledReason,
+            Expr::value(reason.to_string()),
+        )
+        .filter(user_subscriptions::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn touch_last_notified_at(db: &DatabaseConnection, id: i32) -> Result<(), anyhow::Error> {
+    user_subscriptions::Entity::update_many()
+        .col_expr(
+            user_subscriptions::Column::LastNotifiedAt,
+            Expr::current_timestamp(),
+        )
+        .filter(user_subscriptions::Column::Id.eq(id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
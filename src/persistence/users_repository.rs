@@ -0,0 +1,51 @@
+use sea_orm::*;
+
+use crate::entities::{google_users, users};
+
+pub async fn find_google_user(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Option<google_users::Model>, anyhow::Error> {
+    let user = users::Entity::find_by_id(user_id)
+        .find_also_related(google_users::Entity)
+        .one(db)
+        .await?;
+
+    Ok(user.and_then(|(_, google_user)| google_user))
+}
+
+pub async fn find_by_discord_id(
+    db: &DatabaseConnection,
+    discord_id: &str,
+) -> Result<Option<users::Model>, anyhow::Error> {
+    users::Entity::find()
+        .filter(users::Column::DiscordId.eq(discord_id))
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn find_by_google_id(
+    db: &DatabaseConnection,
+    google_id: &str,
+) -> Result<Option<users::Model>, anyhow::Error> {
+    users::Entity::find()
+        .filter(users::Column::GoogleId.eq(google_id))
+        .one(db)
+        .await
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn insert(
+    db: &DatabaseConnection,
+    model: users::ActiveModel,
+) -> Result<users::Model, anyhow::Error> {
+    model.insert(db).await.map_err(anyhow::Error::from)
+}
+
+pub async fn update(
+    db: &DatabaseConnection,
+    model: users::ActiveModel,
+) -> Result<users::Model, anyhow::Error> {
+    model.update(db).await.map_err(anyhow::Error::from)
+}
@@ -0,0 +1,67 @@
+use sha2::{Digest, Sha256};
+
+use crate::entities::users;
+
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Who performs a privileged action, either a signed-in admin or an API key from `ADMIN_API_KEYS`.
+#[derive(Debug, Clone)]
+pub enum Admin {
+    User(users::Model),
+    ApiKey(String),
+}
+
+pub fn is_admin(user: &users::Model) -> bool {
+    user.role == ADMIN_ROLE
+}
+
+/// Static keys for scripts and cron jobs, configured as `ADMIN_API_KEYS=name:key,name:key`.
+///
+/// The name identifies the key in the audit log, so that keys can be rotated one at a time.
+#[derive(Debug, Default)]
+pub struct ApiKeys(Vec<(String, String)>);
+
+impl ApiKeys {
+    pub fn from_env() -> Self {
+        std::env::var("ADMIN_API_KEYS")
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    fn parse(value: &str) -> Self {
+        ApiKeys(
+            value
+                .split(',')
+                .filter_map(|entry| entry.trim().split_once(':'))
+                .map(|(name, key)| (name.trim().to_string(), key.trim().to_string()))
+                .filter(|(name, key)| !name.is_empty() && !key.is_empty())
+                .collect(),
+        )
+    }
+
+    /// Returns the name of the matching key.
+    pub fn find(&self, key: &str) -> Option<&str> {
+        // Comparing digests keeps the comparison time independent of how much of the key matches.
+        let digest = Sha256::digest(key.as_bytes());
+        self.0
+            .iter()
+            .find(|(_, candidate)| Sha256::digest(candidate.as_bytes()) == digest)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_keys() {
+        let keys = ApiKeys::parse("cron:abc, deploy : xyz ,broken,empty:");
+
+        assert_eq!(keys.find("abc"), Some("cron"));
+        assert_eq!(keys.find("xyz"), Some("deploy"));
+        assert_eq!(keys.find("broken"), None);
+        assert_eq!(keys.find(""), None);
+        assert_eq!(ApiKeys::parse("").find(""), None);
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::Utc;
+use sea_orm::Set;
+use tracing::error;
+
+use crate::Connections;
+use crate::entities::audit_logs;
+use crate::persistence::audit_logs_repository;
+use crate::services::admin::access_service::Admin;
+
+/// Records a privileged action and who performed it.
+///
+/// Called once the action happened, so a failure is logged instead of failing the request, which
+/// would only make the admin repeat the action.
+pub async fn record(conns: &Connections, admin: &Admin, action: &str, details: serde_json::Value) {
+    let (user_id, api_key_name) = match admin {
+        Admin::User(user) => (Some(user.id), None),
+        Admin::ApiKey(name) => (None, Some(name.clone())),
+    };
+
+    let result = audit_logs_repository::insert(
+        &conns.db,
+        audit_logs::ActiveModel {
+            id: Default::default(),
+            user_id: Set(user_id),
+            api_key_name: Set(api_key_name.clone()),
+            action: Set(action.to_string()),
+            details: Set(Some(details.clone())),
+            created_at: Set(Utc::now().fixed_offset()),
+        },
+    )
+    .await;
+
+    if let Err(err) = result {
+        error!(
+            error = %err,
+            action,
+            user_id,
+            api_key_name,
+            %details,
+            "failed to record audit log"
+        );
+    }
+}
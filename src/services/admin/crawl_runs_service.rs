@@ -0,0 +1,108 @@
+use anyhow::anyhow;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::{crawl_rejections, crawl_runs};
+use crate::error::ApiError;
+use crate::persistence::crawl_runs_repository;
+
+const MAX_LIMIT: u64 = 1000;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CrawlRun {
+    pub id: i32,
+    /// `csv:<path>` or `http:<base URL>`.
+    pub source: String,
+    pub started_at: DateTime<FixedOffset>,
+    /// Missing while the run is in progress.
+    pub finished_at: Option<DateTime<FixedOffset>>,
+    pub rows_read: i32,
+    pub rows_skipped: i32,
+    /// E.g. `invalid_latitude`, `invalid_datetime` or `malformed_record`.
+    pub skipped_by_reason: BTreeMap<String, i32>,
+    pub organizers_created: i32,
+    pub events_inserted: i32,
+    pub events_updated: i32,
+    /// Why the run stopped early, if it did.
+    pub error: Option<String>,
+}
+
+impl From<crawl_runs::Model> for CrawlRun {
+    fn from(model: crawl_runs::Model) -> Self {
+        CrawlRun {
+            id: model.id,
+            source: model.source,
+            started_at: model.started_at,
+            finished_at: model.finished_at,
+            rows_read: model.rows_read,
+            rows_skipped: model.rows_skipped,
+            skipped_by_reason: serde_json::from_value(model.skipped_by_reason).unwrap_or_default(),
+            organizers_created: model.organizers_created,
+            events_inserted: model.events_inserted,
+            events_updated: model.events_updated,
+            error: model.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CrawlRejection {
+    pub id: i32,
+    /// Position of the record in the source, starting at 1.
+    pub record_number: i32,
+    pub reason: String,
+    pub error: String,
+    /// The line or object the record was read from.
+    pub raw: Option<String>,
+}
+
+impl From<crawl_rejections::Model> for CrawlRejection {
+    fn from(model: crawl_rejections::Model) -> Self {
+        CrawlRejection {
+            id: model.id,
+            record_number: model.record_number,
+            reason: model.reason,
+            error: model.error,
+            raw: model.raw,
+        }
+    }
+}
+
+pub async fn list(conns: &Connections, limit: u64) -> Result<Vec<CrawlRun>, ApiError> {
+    let models = crawl_runs_repository::latest(&conns.db, limit.clamp(1, MAX_LIMIT)).await?;
+
+    Ok(models.into_iter().map(CrawlRun::from).collect())
+}
+
+pub async fn last(conns: &Connections) -> Result<CrawlRun, ApiError> {
+    crawl_runs_repository::latest(&conns.db, 1)
+        .await?
+        .into_iter()
+        .next()
+        .map(CrawlRun::from)
+        .ok_or_else(|| ApiError::not_found(anyhow!("the crawler has not run yet")))
+}
+
+pub async fn show(conns: &Connections, id: i32) -> Result<CrawlRun, ApiError> {
+    crawl_runs_repository::find_by_id(&conns.db, id)
+        .await?
+        .map(CrawlRun::from)
+        .ok_or_else(|| ApiError::not_found(anyhow!("crawl run {id} not found")))
+}
+
+pub async fn rejections(
+    conns: &Connections,
+    id: i32,
+    after_id: Option<i32>,
+    limit: u64,
+) -> Result<Vec<CrawlRejection>, ApiError> {
+    show(conns, id).await?;
+    let models =
+        crawl_runs_repository::rejections(&conns.db, id, after_id, limit.clamp(1, MAX_LIMIT))
+            .await?;
+
+    Ok(models.into_iter().map(CrawlRejection::from).collect())
+}
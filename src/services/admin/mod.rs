@@ -0,0 +1,3 @@
+pub mod access_service;
+pub mod audit_service;
+pub mod crawl_runs_service;
@@ -0,0 +1,166 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use sea_orm::*;
+
+use crate::Connections;
+use crate::entities::{events, organizers};
+use crate::error::ApiError;
+use crate::services::events::presentation;
+use crate::services::events::search_service::{self, EventsSearchFilters};
+
+/// Calendar clients poll the whole feed, so it is capped instead of paginated. The events
+/// nearest to now are kept, with separate caps so that history never pushes out upcoming events.
+const MAX_UPCOMING_CALENDAR_EVENTS: u64 = 1000;
+const MAX_PAST_CALENDAR_EVENTS: u64 = 200;
+
+/// Without an explicit time filter recently finished events stay in the feed for this long.
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+
+/// The data has no end times, League Cups and Challenges usually take an afternoon.
+const EVENT_DURATION: &str = "PT4H";
+
+/// Renders all events matching `filters` as an RFC 5545 iCalendar feed.
+pub async fn calendar(
+    conns: &Connections,
+    mut filters: EventsSearchFilters,
+) -> Result<String, ApiError> {
+    if filters.state.is_none() && filters.happening_from.is_none() {
+        filters.happening_from = Some((Utc::now() - Duration::days(DEFAULT_HISTORY_DAYS)).into());
+    }
+
+    let now = Utc::now();
+    let mut rows = search_service::filter_query(filters.clone())?
+        .filter(events::Column::HappeningAt.lt(now))
+        .order_by(events::Column::HappeningAt, Order::Desc)
+        .order_by(events::Column::Id, Order::Desc)
+        .limit(MAX_PAST_CALENDAR_EVENTS)
+        .select_also(organizers::Entity)
+        .all(&conns.db)
+        .await?;
+    rows.reverse();
+    rows.extend(
+        search_service::filter_query(filters)?
+            .filter(events::Column::HappeningAt.gte(now))
+            .order_by(events::Column::HappeningAt, Order::Asc)
+            .order_by(events::Column::Id, Order::Asc)
+            .limit(MAX_UPCOMING_CALENDAR_EVENTS)
+            .select_also(organizers::Entity)
+            .all(&conns.db)
+            .await?,
+    );
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//poketcgevents//events//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Pokémon TCG events".to_string(),
+    ];
+    for (event, organizer) in rows {
+        if let Some(organizer) = organizer {
+            lines.extend(vevent(&event, &organizer));
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines
+        .iter()
+        .map(|line| fold_line(line) + "\r\n")
+        .collect::<String>())
+}
+
+fn vevent(event: &events::Model, organizer: &organizers::Model) -> Vec<String> {
+    let location = [
+        organizer.name.as_str(),
+        organizer.address.as_str(),
+        organizer.city.as_str(),
+        organizer.country.as_str(),
+    ]
+    .join(", ");
+
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", event.guid),
+        format!("DTSTAMP:{}", utc_date_time(&event.updated_at)),
+        // In UTC, a `TZID` requires a matching VTIMEZONE (RFC 5545, section 3.2.19) which
+        // Outlook and others do not resolve from the IANA name.
+        format!("DTSTART:{}", utc_date_time(&event.happening_at)),
+        format!("DURATION:{EVENT_DURATION}"),
+        format!("SUMMARY:{}", escape_text(&event.name)),
+        format!("DESCRIPTION:{}", escape_text(&event.kind)),
+        format!("LOCATION:{}", escape_text(&location)),
+        format!("GEO:{:.6};{:.6}", organizer.latitude, organizer.longitude),
+        format!("URL:{}", presentation::event_url(event)),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+/// DATE-TIME value in UTC (RFC 5545, section 3.3.5).
+fn utc_date_time(time: &DateTime<FixedOffset>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escapes a TEXT property value (RFC 5545, section 3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds content lines longer than 75 octets without splitting UTF-8 characters
+/// (RFC 5545, section 3.1).
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_date_time() {
+        let time = DateTime::parse_from_rfc3339("2026-10-18T10:00:00+02:00").unwrap();
+        assert_eq!(utc_date_time(&time), "20261018T080000Z");
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(
+            escape_text("Cup; Köln, DE\\2\r\nDay"),
+            "Cup\\; Köln\\, DE\\\\2\\nDay"
+        );
+    }
+
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(fold_line("SUMMARY:short"), "SUMMARY:short");
+
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_line(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
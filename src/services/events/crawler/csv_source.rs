@@ -0,0 +1,219 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use csv::{ReaderBuilder, StringRecord};
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::source::{EventRecord, EventRecords, EventSource, RejectedRecord};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvSourceConfig {
+    /// Relative to the data directory and must not leave it, see [`super::source::data_dir`].
+    pub path: PathBuf,
+    pub delimiter: char,
+    pub columns: CsvColumns,
+}
+
+impl Default for CsvSourceConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("events.csv"),
+            delimiter: ';',
+            columns: CsvColumns::default(),
+        }
+    }
+}
+
+/// Header names of the record fields, the defaults match the original export and
+/// `export_service`, so that regional exports only need to override what differs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvColumns {
+    pub kind: String,
+    pub name: String,
+    pub shop: String,
+    pub street_address: String,
+    pub state: String,
+    pub city: String,
+    pub country_code: String,
+    pub pokemon_event_slug: String,
+    pub guid: String,
+    pub latitude: String,
+    pub longitude: String,
+    pub happening_at: String,
+    pub league: String,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        Self {
+            kind: "type".to_string(),
+            name: "name".to_string(),
+            shop: "shop".to_string(),
+            street_address: "street_adress".to_string(),
+            state: "state".to_string(),
+            city: "city".to_string(),
+            country_code: "country_code".to_string(),
+            pokemon_event_slug: "pokemon_url".to_string(),
+            guid: "guid".to_string(),
+            latitude: "latitude".to_string(),
+            longitude: "longitude".to_string(),
+            happening_at: "when".to_string(),
+            league: "league".to_string(),
+        }
+    }
+}
+
+/// Positions of the configured columns in the header of a file.
+struct ColumnIndices([usize; 13]);
+
+impl ColumnIndices {
+    fn resolve(columns: &CsvColumns, headers: &StringRecord) -> Result<Self, anyhow::Error> {
+        let names = [
+            &columns.kind,
+            &columns.name,
+            &columns.shop,
+            &columns.street_address,
+            &columns.state,
+            &columns.city,
+            &columns.country_code,
+            &columns.pokemon_event_slug,
+            &columns.guid,
+            &columns.latitude,
+            &columns.longitude,
+            &columns.happening_at,
+            &columns.league,
+        ];
+        let mut indices = [0; 13];
+        for (index, name) in indices.iter_mut().zip(names) {
+            *index = headers
+                .iter()
+                .position(|header| header.trim() == name)
+                .ok_or_else(|| anyhow!("column `{name}` is missing"))?;
+        }
+
+        Ok(Self(indices))
+    }
+
+    fn record(&self, row: &StringRecord, delimiter: char) -> Result<EventRecord, RejectedRecord> {
+        let raw = row.iter().collect::<Vec<_>>().join(&delimiter.to_string());
+        let field = |index: usize| {
+            row.get(self.0[index])
+                .map(str::to_string)
+                .ok_or_else(|| RejectedRecord {
+                    error: anyhow!("row has only {} columns", row.len()),
+                    raw: Some(raw.clone()),
+                })
+        };
+
+        Ok(EventRecord {
+            kind: field(0)?,
+            name: field(1)?,
+            shop: field(2)?,
+            street_address: field(3)?,
+            state: field(4)?,
+            city: field(5)?,
+            country_code: field(6)?,
+            pokemon_event_slug: field(7)?,
+            guid: field(8)?,
+            latitude: field(9)?,
+            longitude: field(10)?,
+            happening_at: field(11)?,
+            league: field(12)?,
+            raw,
+        })
+    }
+}
+
+/// Reads a delimited file with a header row.
+pub struct CsvSource {
+    config: CsvSourceConfig,
+}
+
+impl CsvSource {
+    pub fn new(config: CsvSourceConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl EventSource for CsvSource {
+    fn name(&self) -> String {
+        format!("csv:{}", self.config.path.display())
+    }
+
+    async fn records(&self) -> Result<EventRecords, anyhow::Error> {
+        let delimiter = u8::try_from(self.config.delimiter)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or_else(|| anyhow!("CSV delimiter must be an ASCII character"))?;
+        // Rows with missing columns are rejected one by one rather than failing the reader.
+        let mut reader = ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_path(&self.config.path)
+            .with_context(|| format!("failed to open {}", self.config.path.display()))?;
+        let headers = reader.headers().context("failed to read CSV header")?;
+        let indices = ColumnIndices::resolve(&self.config.columns, headers)
+            .with_context(|| format!("unexpected header in {}", self.config.path.display()))?;
+
+        let records = reader.into_records().map(move |row| {
+            let row = row
+                .context("malformed CSV row")
+                .map_err(RejectedRecord::from)?;
+            Ok(indices.record(&row, char::from(delimiter))?)
+        });
+
+        Ok(stream::iter(records).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::events::crawler::source::RecordError;
+
+    #[tokio::test]
+    async fn test_column_mapping() {
+        let path = std::env::temp_dir().join(format!("events-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "kind,title,shop,street_adress,state,city,country_code,pokemon_url,guid,lat,lng,when,league,extra\n\
+             League Cup,Köln Cup,Cards,Main 1,NRW,Köln,DE,league-cup-koln,6d1f3c49-2d43-4f7a-9b5e-4b7d8f0c2a11,50.9,6.9,2026-10-24 10:00:00,,x\n\
+             broken\n",
+        )
+        .unwrap();
+
+        let source = CsvSource::new(CsvSourceConfig {
+            path: path.clone(),
+            delimiter: ',',
+            columns: CsvColumns {
+                kind: "kind".to_string(),
+                name: "title".to_string(),
+                latitude: "lat".to_string(),
+                longitude: "lng".to_string(),
+                ..Default::default()
+            },
+        });
+        let records = source.records().await.unwrap().collect::<Vec<_>>().await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(records.len(), 2);
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(record.name, "Köln Cup");
+        assert_eq!(record.latitude, "50.9");
+        assert_eq!(record.happening_at, "2026-10-24 10:00:00");
+        assert!(matches!(
+            &records[1],
+            Err(RecordError::Rejected(RejectedRecord { raw: Some(raw), .. })) if raw == "broken"
+        ));
+
+        let source = CsvSource::new(CsvSourceConfig {
+            path: path.clone(),
+            ..Default::default()
+        });
+        assert!(source.records().await.is_err());
+    }
+}
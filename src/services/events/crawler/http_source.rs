@@ -0,0 +1,505 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use futures_util::{StreamExt, stream};
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+use super::csv_source::CsvColumns;
+use super::source::{
+    EventRecord, EventRecords, EventSource, RecordError, RejectedRecord, data_dir,
+};
+use crate::services::retry::RetryPolicy;
+
+const USER_AGENT: &str = "poketcgevents-api crawler (+https://poketcgevents-api.onrender.com)";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpSourceMode {
+    /// Fetches pages over HTTP and records every response to `responses_dir`.
+    #[default]
+    Live,
+    /// Reads previously recorded responses from `responses_dir` without touching the network.
+    Replay,
+}
+
+/// A paged JSON listing, requested as `base_url?<page_param>=<page>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSourceConfig {
+    pub base_url: String,
+    pub page_param: String,
+    pub first_page: u32,
+    /// Stops there even if the listing reports more pages.
+    pub max_pages: u32,
+    /// JSON pointer to the array of events in a page.
+    pub items_pointer: String,
+    /// JSON pointer to the number of pages, a listing without it is read as a single page.
+    pub total_pages_pointer: String,
+    /// Keys of the event objects, named like the CSV columns by default. Keys starting with `/`
+    /// are JSON pointers for nested values.
+    pub fields: CsvColumns,
+    /// Upper bound of requests in flight, keep it low to stay polite.
+    pub concurrency: usize,
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub mode: HttpSourceMode,
+    /// Recorded responses with their `ETag`/`Last-Modified`, used for conditional requests and
+    /// as fixtures in replay mode. Always `http_responses` in the data directory, a request must
+    /// not choose where files are written.
+    #[serde(skip)]
+    pub responses_dir: PathBuf,
+}
+
+impl Default for HttpSourceConfig {
+    fn default() -> Self {
+        Self {
+            base_url: String::new(),
+            page_param: "page".to_string(),
+            first_page: 1,
+            max_pages: 100,
+            items_pointer: "/events".to_string(),
+            total_pages_pointer: "/total_pages".to_string(),
+            fields: CsvColumns::default(),
+            concurrency: 2,
+            max_attempts: 3,
+            initial_backoff_ms: 1000,
+            mode: HttpSourceMode::default(),
+            responses_dir: data_dir().join("http_responses"),
+        }
+    }
+}
+
+/// Response body as recorded on disk, keyed by its URL.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedResponse {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: Value,
+}
+
+pub struct HttpSource {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    config: HttpSourceConfig,
+    client: reqwest::Client,
+}
+
+impl HttpSource {
+    pub fn new(config: HttpSourceConfig) -> Result<Self, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner { config, client }),
+        })
+    }
+}
+
+#[async_trait]
+impl EventSource for HttpSource {
+    fn name(&self) -> String {
+        format!("http:{}", self.inner.config.base_url)
+    }
+
+    async fn records(&self) -> Result<EventRecords, anyhow::Error> {
+        let inner = self.inner.clone();
+        let HttpSourceConfig {
+            first_page,
+            max_pages,
+            concurrency,
+            ..
+        } = inner.config;
+
+        let first = inner.page(first_page).await?;
+        let total_pages = first
+            .pointer(&inner.config.total_pages_pointer)
+            .and_then(Value::as_u64)
+            .map_or(1, |total| u32::try_from(total).unwrap_or(u32::MAX))
+            .clamp(1, std::cmp::max(max_pages, 1));
+        let first_records = inner.records(&first)?;
+        let rest = (1..total_pages).map(move |offset| first_page.saturating_add(offset));
+
+        let pages = stream::iter(rest)
+            .map(move |page| {
+                let inner = inner.clone();
+                async move {
+                    let body = inner.page(page).await?;
+                    inner.records(&body)
+                }
+            })
+            .buffered(std::cmp::max(concurrency, 1));
+
+        Ok(stream::once(async { Ok(first_records) })
+            .chain(pages)
+            .flat_map(|page: Result<Vec<_>, anyhow::Error>| match page {
+                Ok(records) => stream::iter(records)
+                    .map(|record| record.map_err(RecordError::from))
+                    .boxed(),
+                Err(err) => stream::once(async { Err(RecordError::Failed(err)) }).boxed(),
+            })
+            .boxed())
+    }
+}
+
+impl Inner {
+    fn page_url(&self, page: u32) -> Result<String, anyhow::Error> {
+        let mut url = Url::parse(&self.config.base_url).context("invalid base URL")?;
+        url.query_pairs_mut()
+            .append_pair(&self.config.page_param, &page.to_string());
+
+        Ok(url.into())
+    }
+
+    async fn page(&self, page: u32) -> Result<Value, anyhow::Error> {
+        let url = self.page_url(page)?;
+        let recorded = self.read_recorded(&url).await;
+
+        if self.config.mode == HttpSourceMode::Replay {
+            return recorded
+                .map(|recorded| recorded.body)
+                .ok_or_else(|| anyhow!("no recorded response for {url}"));
+        }
+
+        let policy = RetryPolicy {
+            max_attempts: std::cmp::max(self.config.max_attempts, 1),
+            initial_backoff: Duration::from_millis(self.config.initial_backoff_ms),
+            ..Default::default()
+        };
+        let mut attempt = 1;
+        loop {
+            match self.fetch(&url, recorded.as_ref()).await {
+                Ok(Some(response)) => {
+                    let body = response.body.clone();
+                    self.record(&response).await;
+                    return Ok(body);
+                }
+                Ok(None) => {
+                    info!(url, "Page is not modified since the last run");
+                    return recorded
+                        .map(|recorded| recorded.body)
+                        .ok_or_else(|| anyhow!("{url} is not modified but was never recorded"));
+                }
+                Err(Fetch::Permanent(err)) => return Err(err),
+                Err(Fetch::Retryable { error, retry_after }) => {
+                    if attempt >= policy.max_attempts {
+                        return Err(error);
+                    }
+                    let backoff = retry_after.unwrap_or_else(|| policy.backoff(attempt));
+                    warn!(error = %error, url, attempt, "page request failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns `None` when the recorded response is still current.
+    async fn fetch(
+        &self,
+        url: &str,
+        recorded: Option<&RecordedResponse>,
+    ) -> Result<Option<RecordedResponse>, Fetch> {
+        let mut request = self.client.get(url);
+        if let Some(recorded) = recorded {
+            if let Some(etag) = &recorded.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &recorded.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await.map_err(|err| Fetch::Retryable {
+            error: err.into(),
+            retry_after: None,
+        })?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED && recorded.is_some() {
+            return Ok(None);
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(Fetch::Retryable {
+                error: anyhow!("{url} responded with {status}"),
+                retry_after,
+            });
+        }
+        if !status.is_success() {
+            return Err(Fetch::Permanent(anyhow!("{url} responded with {status}")));
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response
+            .json::<Value>()
+            .await
+            .map_err(|err| Fetch::Retryable {
+                error: anyhow::Error::from(err).context(format!("invalid JSON from {url}")),
+                retry_after: None,
+            })?;
+
+        Ok(Some(RecordedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            body,
+        }))
+    }
+
+    fn recording_path(&self, url: &str) -> PathBuf {
+        let digest = format!("{:x}", Sha256::digest(url.as_bytes()));
+        self.config
+            .responses_dir
+            .join(format!("{}.json", &digest[..16]))
+    }
+
+    async fn read_recorded(&self, url: &str) -> Option<RecordedResponse> {
+        let content = tokio::fs::read(self.recording_path(url)).await.ok()?;
+        serde_json::from_slice::<RecordedResponse>(&content)
+            .ok()
+            .filter(|recorded| recorded.url == url)
+    }
+
+    /// Failing to record only costs a full download next time, so it does not fail the run.
+    async fn record(&self, response: &RecordedResponse) {
+        let result = async {
+            tokio::fs::create_dir_all(&self.config.responses_dir).await?;
+            tokio::fs::write(
+                self.recording_path(&response.url),
+                serde_json::to_vec_pretty(response)?,
+            )
+            .await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+
+        if let Err(err) = result {
+            warn!(error = %err, url = response.url, "failed to record response");
+        }
+    }
+
+    fn records(
+        &self,
+        body: &Value,
+    ) -> Result<Vec<Result<EventRecord, RejectedRecord>>, anyhow::Error> {
+        let items = body
+            .pointer(&self.config.items_pointer)
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("no `{}` array in page", self.config.items_pointer))?;
+
+        Ok(items.iter().map(|item| self.record_from(item)).collect())
+    }
+
+    fn record_from(&self, item: &Value) -> Result<EventRecord, RejectedRecord> {
+        if !item.is_object() {
+            return Err(RejectedRecord {
+                error: anyhow!("event is not an object"),
+                raw: Some(item.to_string()),
+            });
+        }
+        let fields = &self.config.fields;
+        let field = |key: &str| {
+            let value = if key.starts_with('/') {
+                item.pointer(key)
+            } else {
+                item.get(key)
+            };
+            match value {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(value)) => value.clone(),
+                Some(value) => value.to_string(),
+            }
+        };
+
+        Ok(EventRecord {
+            kind: field(&fields.kind),
+            name: field(&fields.name),
+            shop: field(&fields.shop),
+            street_address: field(&fields.street_address),
+            state: field(&fields.state),
+            city: field(&fields.city),
+            country_code: field(&fields.country_code),
+            pokemon_event_slug: field(&fields.pokemon_event_slug),
+            guid: field(&fields.guid),
+            latitude: field(&fields.latitude),
+            longitude: field(&fields.longitude),
+            happening_at: field(&fields.happening_at),
+            league: field(&fields.league),
+            raw: item.to_string(),
+        })
+    }
+}
+
+enum Fetch {
+    Retryable {
+        error: anyhow::Error,
+        retry_after: Option<Duration>,
+    },
+    Permanent(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        extract::{Query, State},
+        http::{HeaderMap, header},
+        response::IntoResponse,
+        routing::get,
+    };
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Requests {
+        count: usize,
+        not_modified: usize,
+        failed_once: bool,
+    }
+
+    async fn listing(
+        State(requests): State<Arc<Mutex<Requests>>>,
+        Query(query): Query<HashMap<String, String>>,
+        headers: HeaderMap,
+    ) -> axum::response::Response {
+        let page = query["p"].parse::<u32>().unwrap();
+        let mut requests = requests.lock().unwrap();
+        requests.count += 1;
+        if page == 2 && !requests.failed_once {
+            requests.failed_once = true;
+            return StatusCode::SERVICE_UNAVAILABLE.into_response();
+        }
+        let etag = format!("\"page-{page}\"");
+        if headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            == Some(&etag)
+        {
+            requests.not_modified += 1;
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+
+        let body = serde_json::json!({
+            "data": {
+                "items": [{
+                    "type": "League Cup",
+                    "name": format!("Cup {page}"),
+                    "store": { "name": "Cards" },
+                    "latitude": 50.9,
+                    "longitude": 6.9,
+                    "when": "2026-10-24 10:00:00",
+                    "league": null,
+                }],
+                "pages": 3,
+            }
+        });
+        ([(header::ETAG, etag)], Json(body)).into_response()
+    }
+
+    #[tokio::test]
+    async fn test_http_source() {
+        let requests = Arc::new(Mutex::new(Requests::default()));
+        let app = Router::new()
+            .route("/events", get(listing))
+            .with_state(requests.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let responses_dir =
+            std::env::temp_dir().join(format!("responses-{}", uuid::Uuid::new_v4()));
+        let config = HttpSourceConfig {
+            base_url: format!("{base}/events"),
+            page_param: "p".to_string(),
+            items_pointer: "/data/items".to_string(),
+            total_pages_pointer: "/data/pages".to_string(),
+            fields: CsvColumns {
+                shop: "/store/name".to_string(),
+                ..Default::default()
+            },
+            initial_backoff_ms: 1,
+            responses_dir: responses_dir.clone(),
+            ..Default::default()
+        };
+        let names = |records: Vec<Result<EventRecord, RecordError>>| {
+            records
+                .into_iter()
+                .map(|record| {
+                    let record = record.unwrap();
+                    assert_eq!(record.shop, "Cards");
+                    assert_eq!(record.latitude, "50.9");
+                    assert_eq!(record.league, "");
+                    record.name
+                })
+                .collect::<Vec<_>>()
+        };
+        let fetch = |config: HttpSourceConfig| async move {
+            let source = HttpSource::new(config).unwrap();
+            source.records().await.unwrap().collect::<Vec<_>>().await
+        };
+
+        let records = fetch(config.clone()).await;
+        assert_eq!(names(records), ["Cup 1", "Cup 2", "Cup 3"]);
+        assert_eq!(requests.lock().unwrap().count, 4);
+
+        let records = fetch(config.clone()).await;
+        assert_eq!(names(records), ["Cup 1", "Cup 2", "Cup 3"]);
+        assert_eq!(requests.lock().unwrap().not_modified, 3);
+
+        let records = fetch(HttpSourceConfig {
+            mode: HttpSourceMode::Replay,
+            ..config.clone()
+        })
+        .await;
+        assert_eq!(names(records), ["Cup 1", "Cup 2", "Cup 3"]);
+        assert_eq!(requests.lock().unwrap().count, 7);
+
+        // A page that still fails after all attempts fails the run instead of being skipped.
+        requests.lock().unwrap().failed_once = false;
+        let records = fetch(HttpSourceConfig {
+            max_attempts: 1,
+            ..config
+        })
+        .await;
+        assert_eq!(records.len(), 3);
+        assert!(matches!(records[1], Err(RecordError::Failed(_))));
+
+        std::fs::remove_dir_all(&responses_dir).unwrap();
+    }
+
+    #[test]
+    fn test_responses_dir_is_not_configurable() {
+        let config: HttpSourceConfig = serde_json::from_value(serde_json::json!({
+            "base_url": "https://example.com/events",
+            "responses_dir": "/etc",
+        }))
+        .unwrap();
+        assert_eq!(config.responses_dir, data_dir().join("http_responses"));
+    }
+}
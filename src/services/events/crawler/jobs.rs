@@ -0,0 +1,388 @@
+use anyhow::anyhow;
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::Set;
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use super::scheduler::JOB_NAME;
+use super::source::{EventSource, SourceConfig};
+use crate::Connections;
+use crate::entities::crawl_runs;
+use crate::error::ApiError;
+use crate::persistence::crawl_runs_repository;
+use crate::persistence::scheduled_jobs_repository::{self, JobLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlJobStatus {
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+/// What started a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlTrigger {
+    Api,
+    Scheduler,
+}
+
+impl CrawlTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Scheduler => "scheduler",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "api" => Some(Self::Api),
+            "scheduler" => Some(Self::Scheduler),
+            _ => None,
+        }
+    }
+}
+
+/// A crawl run as seen by whoever started or polls it.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawlJob {
+    /// The run's id in `crawl_runs`, also for its rejected rows.
+    pub id: i32,
+    pub status: CrawlJobStatus,
+    /// Missing for runs recorded before crawls ran as jobs.
+    pub trigger: Option<CrawlTrigger>,
+    pub source: String,
+    pub started_at: DateTime<FixedOffset>,
+    pub finished_at: Option<DateTime<FixedOffset>>,
+    pub rows_read: i32,
+    pub events_upserted: i32,
+    pub error: Option<String>,
+}
+
+impl From<crawl_runs::Model> for CrawlJob {
+    fn from(run: crawl_runs::Model) -> Self {
+        let status = status(&run);
+        CrawlJob {
+            id: run.id,
+            status,
+            trigger: run.trigger.as_deref().and_then(CrawlTrigger::parse),
+            source: run.source,
+            started_at: run.started_at,
+            finished_at: run.finished_at,
+            rows_read: run.rows_read,
+            events_upserted: run.events_inserted + run.events_updated,
+            error: run.error.filter(|_| status == CrawlJobStatus::Failed),
+        }
+    }
+}
+
+fn status(run: &crawl_runs::Model) -> CrawlJobStatus {
+    match (&run.finished_at, &run.error, &run.cancel_requested_at) {
+        (None, _, _) => CrawlJobStatus::Running,
+        (Some(_), None, _) => CrawlJobStatus::Finished,
+        (Some(_), Some(_), Some(_)) => CrawlJobStatus::Cancelled,
+        (Some(_), Some(_), None) => CrawlJobStatus::Failed,
+    }
+}
+
+/// Crawls running in the background, started through the API or by the scheduler.
+///
+/// A job holds the crawler's advisory lock while it runs, so there is at most one crawl across
+/// all replicas. Its status, progress and cancellation live in its `crawl_runs` row, so that
+/// every replica can serve them.
+#[derive(Default)]
+pub struct CrawlJobs {
+    /// Serializes starting jobs, so that concurrent triggers attach to the same job rather than
+    /// losing the race for the lock.
+    starting: tokio::sync::Mutex<()>,
+}
+
+impl CrawlJobs {
+    /// Starts a crawl of `source`, or returns the running job when it crawls the same source,
+    /// also when it runs on another replica.
+    ///
+    /// The flag tells whether the job was started by this call.
+    pub async fn start(
+        &self,
+        conns: &Connections,
+        source: &SourceConfig,
+    ) -> Result<(CrawlJob, bool), ApiError> {
+        let _starting = self.starting.lock().await;
+        let event_source = source.build().map_err(ApiError::bad_request)?;
+        let Some(lock) = lock(conns).await? else {
+            let run = crawl_runs_repository::running(&conns.db)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::conflict(anyhow!("a crawl is starting on another replica"))
+                })?;
+            let running_source = run
+                .source_config
+                .clone()
+                .and_then(|config| serde_json::from_value::<SourceConfig>(config).ok());
+            if running_source.as_ref() != Some(source) {
+                return Err(ApiError::conflict(anyhow!(
+                    "crawl job {} is running on a different source",
+                    run.id
+                )));
+            }
+            return Ok((CrawlJob::from(run), false));
+        };
+
+        let run = match insert_run(conns, CrawlTrigger::Api, source, event_source.as_ref()).await {
+            Ok(run) => run,
+            Err(err) => {
+                unlock(lock).await;
+                return Err(err.into());
+            }
+        };
+        let job = CrawlJob::from(run.clone());
+        tokio::spawn(run_job(conns.clone(), lock, run, event_source, None));
+
+        Ok((job, true))
+    }
+
+    /// Runs the scheduled crawl of `source` due at `due` to its end, unless a crawl is running
+    /// or the scheduled crawl already ran. Returns the finished job.
+    ///
+    /// The run is recorded even when the crawl fails, a broken source is retried on the next run
+    /// rather than in a loop.
+    pub async fn run_scheduled(
+        &self,
+        conns: &Connections,
+        source: &SourceConfig,
+        due: DateTime<FixedOffset>,
+    ) -> Result<Option<CrawlJob>, anyhow::Error> {
+        let starting = self.starting.lock().await;
+        let Some(lock) = lock(conns).await? else {
+            return Ok(None);
+        };
+
+        let started_at = Utc::now().fixed_offset();
+        let ran = match scheduled_jobs_repository::last_run_at(&conns.db, JOB_NAME).await {
+            Ok(last_run_at) => last_run_at.is_some_and(|last_run_at| last_run_at >= due),
+            Err(err) => {
+                unlock(lock).await;
+                return Err(err);
+            }
+        };
+        if ran {
+            unlock(lock).await;
+            return Ok(None);
+        }
+        let event_source = match source.build() {
+            Ok(event_source) => event_source,
+            Err(err) => {
+                let touched =
+                    scheduled_jobs_repository::touch(&conns.db, JOB_NAME, started_at).await;
+                unlock(lock).await;
+                touched?;
+                return Err(err);
+            }
+        };
+        let run = match insert_run(
+            conns,
+            CrawlTrigger::Scheduler,
+            source,
+            event_source.as_ref(),
+        )
+        .await
+        {
+            Ok(run) => run,
+            Err(err) => {
+                unlock(lock).await;
+                return Err(err);
+            }
+        };
+
+        let handle = tokio::spawn(run_job(
+            conns.clone(),
+            lock,
+            run,
+            event_source,
+            Some(started_at),
+        ));
+        drop(starting);
+
+        Ok(Some(handle.await??))
+    }
+}
+
+pub async fn get(conns: &Connections, id: i32) -> Result<CrawlJob, ApiError> {
+    crawl_runs_repository::find_by_id(&conns.db, id)
+        .await?
+        .map(CrawlJob::from)
+        .ok_or_else(|| ApiError::not_found(anyhow!("crawl job {id} not found")))
+}
+
+/// Asks the job to stop, it is `cancelled` once the crawler noticed, on whichever replica it
+/// runs.
+pub async fn cancel(conns: &Connections, id: i32) -> Result<CrawlJob, ApiError> {
+    let job = get(conns, id).await?;
+    if job.status != CrawlJobStatus::Running
+        || !crawl_runs_repository::request_cancel(&conns.db, id).await?
+    {
+        return Err(ApiError::conflict(anyhow!("crawl job {id} is not running")));
+    }
+
+    Ok(job)
+}
+
+/// Takes the crawler lock. Runs still unfinished at that point were abandoned by a replica that
+/// went away mid-crawl, they are ended so that no trigger attaches to them.
+async fn lock(conns: &Connections) -> Result<Option<JobLock>, anyhow::Error> {
+    let Some(lock) = scheduled_jobs_repository::try_lock(&conns.db, JOB_NAME).await? else {
+        return Ok(None);
+    };
+    match crawl_runs_repository::end_running(&conns.db, None, "crawl run was abandoned").await {
+        Ok(0) => {}
+        Ok(count) => warn!(count, "ended abandoned crawl runs"),
+        Err(err) => {
+            unlock(lock).await;
+            return Err(err);
+        }
+    }
+
+    Ok(Some(lock))
+}
+
+async fn insert_run(
+    conns: &Connections,
+    trigger: CrawlTrigger,
+    config: &SourceConfig,
+    source: &dyn EventSource,
+) -> Result<crawl_runs::Model, anyhow::Error> {
+    crawl_runs_repository::insert(
+        &conns.db,
+        crawl_runs::ActiveModel {
+            id: Default::default(),
+            source: Set(source.name()),
+            started_at: Set(Utc::now().fixed_offset()),
+            finished_at: Set(None),
+            rows_read: Set(0),
+            rows_skipped: Set(0),
+            skipped_by_reason: Set(serde_json::json!({})),
+            organizers_created: Set(0),
+            events_inserted: Set(0),
+            events_updated: Set(0),
+            error: Set(None),
+            trigger: Set(Some(trigger.as_str().to_string())),
+            source_config: Set(Some(serde_json::to_value(config)?)),
+            cancel_requested_at: Set(None),
+        },
+    )
+    .await
+}
+
+/// Crawls, ends the run and releases the lock, and for a scheduled job records the run as of
+/// `scheduled_at`.
+async fn run_job(
+    conns: Connections,
+    lock: JobLock,
+    run: crawl_runs::Model,
+    source: Box<dyn EventSource>,
+    scheduled_at: Option<DateTime<FixedOffset>>,
+) -> Result<CrawlJob, anyhow::Error> {
+    let id = run.id;
+    let db = conns.db.clone();
+    let crawl = tokio::spawn(async move { super::call(&db, source.as_ref(), run).await });
+
+    // Should the crawl panic, its run is ended here instead of staying `running`.
+    if let Err(err) = crawl.await
+        && let Err(err) = crawl_runs_repository::end_running(
+            &conns.db,
+            Some(id),
+            &format!("crawl job ended unexpectedly: {err}"),
+        )
+        .await
+    {
+        error!(error = %err, job_id = id, "failed to end the crawl run");
+    }
+    if let Some(scheduled_at) = scheduled_at
+        && let Err(err) = scheduled_jobs_repository::touch(&conns.db, JOB_NAME, scheduled_at).await
+    {
+        error!(error = %err, job_id = id, "failed to record the scheduled crawl");
+    }
+    unlock(lock).await;
+
+    let job = get(&conns, id).await.map_err(|err| err.error)?;
+    match &job.error {
+        Some(err) => error!(error = %err, job_id = id, "crawl job failed"),
+        None => info!(job_id = id, status = ?job.status, "Crawl job ended"),
+    }
+
+    Ok(job)
+}
+
+async fn unlock(lock: JobLock) {
+    if let Err(err) = lock.unlock().await {
+        warn!(error = %err, "failed to release the crawler lock");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run() -> crawl_runs::Model {
+        let now = Utc::now().fixed_offset();
+        crawl_runs::Model {
+            id: 1,
+            source: "csv:events.csv".to_string(),
+            started_at: now,
+            finished_at: None,
+            rows_read: 10,
+            rows_skipped: 1,
+            skipped_by_reason: serde_json::json!({ "invalid_guid": 1 }),
+            organizers_created: 0,
+            events_inserted: 4,
+            events_updated: 5,
+            error: None,
+            trigger: Some("scheduler".to_string()),
+            source_config: None,
+            cancel_requested_at: None,
+        }
+    }
+
+    #[test]
+    fn test_crawl_job_from_run() {
+        let job = CrawlJob::from(run());
+        assert_eq!(job.status, CrawlJobStatus::Running);
+        assert_eq!(job.trigger, Some(CrawlTrigger::Scheduler));
+        assert_eq!(job.events_upserted, 9);
+
+        let now = Some(Utc::now().fixed_offset());
+        let finished = crawl_runs::Model {
+            finished_at: now,
+            ..run()
+        };
+        assert_eq!(
+            CrawlJob::from(finished.clone()).status,
+            CrawlJobStatus::Finished
+        );
+
+        let failed = CrawlJob::from(crawl_runs::Model {
+            error: Some("failed to read csv:events.csv".to_string()),
+            ..finished.clone()
+        });
+        assert_eq!(failed.status, CrawlJobStatus::Failed);
+        assert!(failed.error.is_some());
+
+        let cancelled = CrawlJob::from(crawl_runs::Model {
+            error: Some("crawl was cancelled".to_string()),
+            cancel_requested_at: now,
+            ..finished.clone()
+        });
+        assert_eq!(cancelled.status, CrawlJobStatus::Cancelled);
+        assert_eq!(cancelled.error, None);
+
+        // A run that ended before it noticed the cancellation still finished.
+        let finished_anyway = CrawlJob::from(crawl_runs::Model {
+            cancel_requested_at: now,
+            ..finished
+        });
+        assert_eq!(finished_anyway.status, CrawlJobStatus::Finished);
+    }
+}
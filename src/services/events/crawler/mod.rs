@@ -0,0 +1,514 @@
+pub mod csv_source;
+pub mod http_source;
+pub mod jobs;
+pub mod scheduler;
+pub mod source;
+
+use anyhow::{Context, anyhow};
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use futures_util::StreamExt;
+use sea_orm::{DatabaseConnection, IntoActiveModel, Set};
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+    time::{Duration, Instant},
+};
+use tracing::{error, info, warn};
+use tzf_rs::DefaultFinder;
+use uuid::Uuid;
+
+use crate::{
+    entities::{crawl_rejections, crawl_runs, events, organizers},
+    persistence::{crawl_runs_repository, events_repository, organizers_repository},
+};
+use source::{EventRecord, EventSource, RecordError};
+
+/// How often a running crawl saves its counters and checks whether it was cancelled.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct OrganizerKey {
+    name: String,
+    address: String,
+    city: String,
+    area: String,
+    country: String,
+}
+
+#[derive(Debug, Clone)]
+struct OrganizerValues {
+    name: String,
+    address: String,
+    city: String,
+    area: String,
+    country: String,
+    latitude: f64,
+    longitude: f64,
+    timezone: String,
+}
+
+/// Why a record was skipped, stored with its rejection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SkipReason {
+    MalformedRecord,
+    InvalidLatitude,
+    InvalidLongitude,
+    OrganizerError,
+    InvalidDatetime,
+    InvalidGuid,
+}
+
+impl SkipReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::MalformedRecord => "malformed_record",
+            Self::InvalidLatitude => "invalid_latitude",
+            Self::InvalidLongitude => "invalid_longitude",
+            Self::OrganizerError => "organizer_error",
+            Self::InvalidDatetime => "invalid_datetime",
+            Self::InvalidGuid => "invalid_guid",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CrawlStats {
+    rows_read: i32,
+    skipped: BTreeMap<SkipReason, i32>,
+    organizers_created: i32,
+    events_inserted: i32,
+    events_updated: i32,
+}
+
+/// Upserts the events of `source` together with their organizers and records the outcome in
+/// its run `run`, also when it fails.
+///
+/// The run's counters are saved every few seconds while it progresses, which is also when it
+/// stops if `cancel_requested_at` was set in the meantime.
+pub async fn call(
+    db: &DatabaseConnection,
+    source: &dyn EventSource,
+    run: crawl_runs::Model,
+) -> Result<crawl_runs::Model, anyhow::Error> {
+    let mut stats = CrawlStats::default();
+    let result = crawl(db, source, run.id, &mut stats).await;
+    if let Err(err) = &result {
+        error!(error = %err, crawl_run_id = run.id, "crawl failed");
+    }
+
+    let mut model = run.into_active_model();
+    model.finished_at = Set(Some(Utc::now().fixed_offset()));
+    stats.apply(&mut model);
+    model.error = Set(result.as_ref().err().map(|err| format!("{err:#}")));
+    let run = crawl_runs_repository::update(db, model).await?;
+
+    result.map(|()| run)
+}
+
+async fn crawl(
+    db: &DatabaseConnection,
+    source: &dyn EventSource,
+    crawl_run_id: i32,
+    stats: &mut CrawlStats,
+) -> Result<(), anyhow::Error> {
+    let offset = FixedOffset::east_opt(0).ok_or_else(|| anyhow!("failed to build UTC offset"))?;
+    let now = Utc::now().with_timezone(&offset);
+    let timezone_finder = DefaultFinder::new();
+
+    let source_name = source.name();
+    let mut records = source
+        .records()
+        .await
+        .with_context(|| format!("failed to read {source_name}"))?;
+
+    let mut organizer_cache = load_existing_organizers(db).await?;
+    let mut event_models: Vec<events::ActiveModel> = Vec::new();
+    let mut saved_at = Instant::now();
+
+    while let Some(record_result) = records.next().await {
+        if saved_at.elapsed() >= PROGRESS_INTERVAL {
+            saved_at = Instant::now();
+            if save_progress(db, crawl_run_id, stats).await? {
+                return Err(anyhow!("crawl was cancelled"));
+            }
+        }
+        stats.rows_read += 1;
+        let rejection = Rejection {
+            db,
+            crawl_run_id,
+            record_number: stats.rows_read,
+        };
+
+        let record = match record_result {
+            Ok(r) => r,
+            Err(RecordError::Rejected(rejected)) => {
+                rejection
+                    .record(
+                        stats,
+                        SkipReason::MalformedRecord,
+                        &rejected.error,
+                        rejected.raw,
+                    )
+                    .await?;
+                continue;
+            }
+            Err(RecordError::Failed(err)) => {
+                return Err(err.context(format!("failed to read {source_name}")));
+            }
+        };
+
+        let latitude = match parse_f64(&record.latitude) {
+            Some(lat) => lat,
+            None => {
+                let err = anyhow!("invalid latitude: {}", record.latitude);
+                rejection
+                    .record(stats, SkipReason::InvalidLatitude, &err, Some(record.raw))
+                    .await?;
+                continue;
+            }
+        };
+
+        let longitude = match parse_f64(&record.longitude) {
+            Some(lon) => lon,
+            None => {
+                let err = anyhow!("invalid longitude: {}", record.longitude);
+                rejection
+                    .record(stats, SkipReason::InvalidLongitude, &err, Some(record.raw))
+                    .await?;
+                continue;
+            }
+        };
+
+        let timezone_name = timezone_finder.get_tz_name(longitude, latitude);
+
+        let organizer_values =
+            organizer_values_from_record(&record, latitude, longitude, timezone_name);
+        let organizer_id =
+            match ensure_organizer(db, &mut organizer_cache, stats, organizer_values, now).await {
+                Ok(id) => id,
+                Err(err) => {
+                    rejection
+                        .record(stats, SkipReason::OrganizerError, &err, Some(record.raw))
+                        .await?;
+                    continue;
+                }
+            };
+
+        let happening_at =
+            match parse_datetime_in_timezone(&record.happening_at, timezone_name, offset) {
+                Ok(dt) => dt,
+                Err(err) => {
+                    rejection
+                        .record(stats, SkipReason::InvalidDatetime, &err, Some(record.raw))
+                        .await?;
+                    continue;
+                }
+            };
+
+        let guid = match Uuid::parse_str(record.guid.trim()) {
+            Ok(g) => g,
+            Err(err) => {
+                let err =
+                    anyhow::Error::from(err).context(format!("invalid GUID: {}", record.guid));
+                rejection
+                    .record(stats, SkipReason::InvalidGuid, &err, Some(record.raw))
+                    .await?;
+                continue;
+            }
+        };
+
+        event_models.push(build_event_model(
+            &record,
+            organizer_id,
+            guid,
+            happening_at,
+            now,
+        )?);
+
+        if event_models.len() >= 100 {
+            let chunk = mem::take(&mut event_models);
+            let counts = events_repository::upsert(db, chunk)
+                .await
+                .context("failed to upsert events chunk")?;
+            stats.add(counts);
+        }
+    }
+
+    if !event_models.is_empty() {
+        let counts = events_repository::upsert(db, event_models)
+            .await
+            .context("failed to upsert final events chunk")?;
+        stats.add(counts);
+    }
+
+    info!(
+        events_inserted = stats.events_inserted,
+        events_updated = stats.events_updated,
+        rows_skipped = stats.skipped.values().sum::<i32>(),
+        source = %source_name,
+        "Upserted events"
+    );
+
+    Ok(())
+}
+
+impl CrawlStats {
+    fn add(&mut self, counts: events_repository::UpsertCounts) {
+        self.events_inserted += counts.inserted as i32;
+        self.events_updated += counts.updated as i32;
+    }
+
+    fn apply(&self, model: &mut crawl_runs::ActiveModel) {
+        model.rows_read = Set(self.rows_read);
+        model.rows_skipped = Set(self.skipped.values().sum());
+        model.skipped_by_reason = Set(serde_json::Value::Object(
+            self.skipped
+                .iter()
+                .map(|(reason, count)| (reason.as_str().to_string(), (*count).into()))
+                .collect(),
+        ));
+        model.organizers_created = Set(self.organizers_created);
+        model.events_inserted = Set(self.events_inserted);
+        model.events_updated = Set(self.events_updated);
+    }
+}
+
+/// Saves the counters of a running crawl, returns whether it is to be cancelled.
+async fn save_progress(
+    db: &DatabaseConnection,
+    crawl_run_id: i32,
+    stats: &CrawlStats,
+) -> Result<bool, anyhow::Error> {
+    let mut model = crawl_runs::ActiveModel {
+        id: Set(crawl_run_id),
+        ..Default::default()
+    };
+    stats.apply(&mut model);
+    let run = crawl_runs_repository::update(db, model)
+        .await
+        .context("failed to save crawl progress")?;
+
+    Ok(run.cancel_requested_at.is_some())
+}
+
+/// Skips the current record, keeping the reason and its raw input in `crawl_rejections`.
+struct Rejection<'a> {
+    db: &'a DatabaseConnection,
+    crawl_run_id: i32,
+    record_number: i32,
+}
+
+impl Rejection<'_> {
+    async fn record(
+        &self,
+        stats: &mut CrawlStats,
+        reason: SkipReason,
+        error: &anyhow::Error,
+        raw: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        warn!(
+            error = %error,
+            reason = reason.as_str(),
+            record_number = self.record_number,
+            "skipping record"
+        );
+        *stats.skipped.entry(reason).or_default() += 1;
+
+        crawl_runs_repository::insert_rejection(
+            self.db,
+            crawl_rejections::ActiveModel {
+                id: Default::default(),
+                crawl_run_id: Set(self.crawl_run_id),
+                record_number: Set(self.record_number),
+                reason: Set(reason.as_str().to_string()),
+                error: Set(format!("{error:#}")),
+                raw: Set(raw),
+                created_at: Set(Utc::now().fixed_offset()),
+            },
+        )
+        .await
+        .context("failed to record rejection")
+    }
+}
+
+fn build_event_model(
+    record: &EventRecord,
+    organizer_id: i32,
+    guid: Uuid,
+    happening_at: DateTime<FixedOffset>,
+    now: DateTime<FixedOffset>,
+) -> Result<events::ActiveModel, anyhow::Error> {
+    let league = parse_optional_i32(&record.league);
+
+    Ok(events::ActiveModel {
+        id: Default::default(),
+        organizer_id: Set(organizer_id),
+        kind: Set(record.kind.trim().to_string()),
+        name: Set(record.name.trim().to_string()),
+        pokemon_event_slug: Set(record.pokemon_event_slug.trim().to_string()),
+        guid: Set(guid),
+        league: Set(league),
+        happening_at: Set(happening_at),
+        created_at: Set(now),
+        updated_at: Set(now),
+    })
+}
+
+async fn ensure_organizer(
+    db: &DatabaseConnection,
+    cache: &mut HashMap<OrganizerKey, organizers::Model>,
+    stats: &mut CrawlStats,
+    values: OrganizerValues,
+    now: DateTime<FixedOffset>,
+) -> Result<i32, anyhow::Error> {
+    let key = build_organizer_key(&values);
+
+    if let Some(existing) = cache.get(&key) {
+        return Ok(existing.id);
+    }
+
+    let model = organizers::ActiveModel {
+        id: Default::default(),
+        name: Set(values.name.clone()),
+        address: Set(values.address.clone()),
+        city: Set(values.city.clone()),
+        area: Set(values.area.clone()),
+        country: Set(values.country.clone()),
+        latitude: Set(values.latitude),
+        longitude: Set(values.longitude),
+        timezone: Set(values.timezone.clone()),
+        created_at: Set(now),
+        updated_at: Set(now),
+    };
+
+    let inserted = organizers_repository::insert(db, model)
+        .await
+        .context("failed to insert organizer")?;
+    stats.organizers_created += 1;
+
+    cache.insert(key.clone(), inserted.clone());
+
+    Ok(inserted.id)
+}
+
+fn build_organizer_key(values: &OrganizerValues) -> OrganizerKey {
+    OrganizerKey {
+        name: normalize_key(&values.name),
+        address: normalize_key(&values.address),
+        city: normalize_key(&values.city),
+        area: normalize_key(&values.area),
+        country: normalize_key(&values.country),
+    }
+}
+
+fn organizer_values_from_record(
+    record: &EventRecord,
+    latitude: f64,
+    longitude: f64,
+    timezone_name: &str,
+) -> OrganizerValues {
+    OrganizerValues {
+        name: normalize(&record.shop, "Unknown organizer"),
+        address: normalize(&record.street_address, "Unknown address"),
+        city: normalize(&record.city, "Unknown city"),
+        area: normalize(&record.state, "Unknown area"),
+        country: normalize(&record.country_code, "XX"),
+        latitude,
+        longitude,
+        timezone: timezone_name.to_string(),
+    }
+}
+
+fn organizer_values_from_model(model: &organizers::Model) -> OrganizerValues {
+    OrganizerValues {
+        name: model.name.clone(),
+        address: model.address.clone(),
+        city: model.city.clone(),
+        area: model.area.clone(),
+        country: model.country.clone(),
+        latitude: model.latitude,
+        longitude: model.longitude,
+        timezone: model.timezone.clone(),
+    }
+}
+
+fn parse_f64(value: &str) -> Option<f64> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    trimmed.parse::<f64>().ok()
+}
+
+fn parse_optional_i32(value: &str) -> Option<i32> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    trimmed.parse::<i32>().ok()
+}
+
+fn parse_datetime_in_timezone(
+    value: &str,
+    timezone_name: &str,
+    utc_offset: FixedOffset,
+) -> Result<DateTime<FixedOffset>, anyhow::Error> {
+    let trimmed = value.trim();
+    let naive = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S")
+        .with_context(|| format!("invalid datetime format: {trimmed}"))?;
+
+    let tz: Tz = timezone_name
+        .parse()
+        .with_context(|| format!("failed to parse timezone: {timezone_name}"))?;
+
+    let localized = match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(first, _) => {
+            warn!(
+                timezone = timezone_name,
+                "ambiguous local time, choosing earliest occurrence"
+            );
+            first
+        }
+        LocalResult::None => {
+            return Err(anyhow!(
+                "nonexistent local time {trimmed} in timezone {timezone_name}"
+            ));
+        }
+    };
+
+    let utc = localized.with_timezone(&Utc);
+
+    Ok(utc.with_timezone(&utc_offset))
+}
+
+fn normalize(value: &str, fallback: &str) -> String {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return fallback.to_string();
+    }
+    trimmed.to_string()
+}
+
+fn normalize_key(value: &str) -> String {
+    normalize(value, "").to_ascii_lowercase()
+}
+
+async fn load_existing_organizers(
+    db: &DatabaseConnection,
+) -> Result<HashMap<OrganizerKey, organizers::Model>, anyhow::Error> {
+    let mut map = HashMap::new();
+    let organizers = organizers_repository::all(db)
+        .await
+        .context("failed to load existing organizers")?;
+
+    for organizer in organizers {
+        let values = organizer_values_from_model(&organizer);
+        let key = build_organizer_key(&values);
+
+        map.entry(key).or_insert(organizer);
+    }
+
+    Ok(map)
+}
@@ -0,0 +1,231 @@
+use anyhow::{Context, anyhow};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use super::jobs::CrawlJobs;
+use super::source::SourceConfig;
+use crate::Connections;
+use crate::persistence::scheduled_jobs_repository;
+
+/// Also names the advisory lock that keeps crawls from running concurrently, see [`CrawlJobs`].
+pub const JOB_NAME: &str = "crawler";
+/// Pause after the schedule state could not be read, e.g. while the database is down.
+const ERROR_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Interval(chrono::Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// The first run strictly after `time`, `None` when the cron expression has no more runs.
+    pub fn next_after(&self, time: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Interval(interval) => Some(time + *interval),
+            Self::Cron(schedule) => schedule
+                .after(&time.with_timezone(&Utc))
+                .next()
+                .map(|next| next.fixed_offset()),
+        }
+    }
+}
+
+/// What happens when the process was down at the time of a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissedRuns {
+    /// Runs once right away, however many runs were missed.
+    #[default]
+    RunOnce,
+    /// Waits for the next scheduled run.
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    pub schedule: Schedule,
+    /// Upper bound of a random delay added to every run, so that replicas do not hit the
+    /// database and the sources at the same moment.
+    pub jitter: Duration,
+    pub missed_runs: MissedRuns,
+    pub source: SourceConfig,
+}
+
+impl SchedulerConfig {
+    /// Configured by `CRAWLER_CRON` (with seconds, e.g. `0 0 4 * * *`) or
+    /// `CRAWLER_INTERVAL_SECONDS`, returns `None` when neither is set.
+    ///
+    /// `CRAWLER_JITTER_SECONDS`, `CRAWLER_MISSED_RUNS` (`run_once` or `skip`) and `CRAWLER_SOURCE`
+    /// (the JSON body of `POST /debug/crawler`) are optional.
+    pub fn from_env() -> Result<Option<Self>, anyhow::Error> {
+        let schedule = if let Ok(expression) = std::env::var("CRAWLER_CRON") {
+            Schedule::Cron(Box::new(
+                cron::Schedule::from_str(&expression)
+                    .with_context(|| format!("CRAWLER_CRON is not valid: {expression}"))?,
+            ))
+        } else if let Ok(seconds) = std::env::var("CRAWLER_INTERVAL_SECONDS") {
+            let seconds = seconds
+                .parse::<i64>()
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .context("CRAWLER_INTERVAL_SECONDS must be a positive number")?;
+            Schedule::Interval(chrono::Duration::seconds(seconds))
+        } else {
+            return Ok(None);
+        };
+
+        let jitter_seconds = match std::env::var("CRAWLER_JITTER_SECONDS") {
+            Ok(seconds) => seconds
+                .parse::<u64>()
+                .context("CRAWLER_JITTER_SECONDS must be a number of seconds")?,
+            Err(_) => 0,
+        };
+        let missed_runs = match std::env::var("CRAWLER_MISSED_RUNS").as_deref() {
+            Err(_) | Ok("run_once") => MissedRuns::RunOnce,
+            Ok("skip") => MissedRuns::Skip,
+            Ok(other) => return Err(anyhow!("CRAWLER_MISSED_RUNS is not valid: {other}")),
+        };
+        let source = match std::env::var("CRAWLER_SOURCE") {
+            Ok(json) => serde_json::from_str(&json).context("CRAWLER_SOURCE is not valid")?,
+            Err(_) => SourceConfig::default(),
+        };
+
+        Ok(Some(Self {
+            schedule,
+            jitter: Duration::from_secs(jitter_seconds),
+            missed_runs,
+            source,
+        }))
+    }
+}
+
+/// Runs the crawler on schedule for the lifetime of the process.
+///
+/// Runs are crawl jobs, so that an API trigger during a scheduled crawl attaches to it.
+pub fn spawn(conns: Connections, jobs: Arc<CrawlJobs>, config: SchedulerConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_attempt = None;
+
+        loop {
+            let last_run_at =
+                match scheduled_jobs_repository::last_run_at(&conns.db, JOB_NAME).await {
+                    Ok(last_run_at) => last_run_at,
+                    Err(err) => {
+                        error!(error = %err, "failed to read crawler schedule");
+                        tokio::time::sleep(ERROR_BACKOFF).await;
+                        continue;
+                    }
+                };
+            let now = Utc::now().fixed_offset();
+            let Some(due) = next_run(&config, last_run_at, last_attempt, now) else {
+                warn!("Crawler schedule has no more runs");
+                return;
+            };
+            last_attempt = Some(due);
+
+            let delay = (due - now).to_std().unwrap_or_default() + jitter(config.jitter);
+            tokio::time::sleep(delay).await;
+
+            match jobs.run_scheduled(&conns, &config.source, due).await {
+                Ok(Some(job)) => {
+                    info!(due = %due, job_id = %job.id, status = ?job.status, "Scheduled crawl ended")
+                }
+                Ok(None) => debug!(due = %due, "scheduled crawl ran elsewhere or is running"),
+                Err(err) => error!(error = %err, due = %due, "scheduled crawl failed"),
+            }
+        }
+    })
+}
+
+/// When the next run is due, `now` for a missed run that is to be caught up.
+///
+/// `last_attempt` keeps a replica that lost the lock from retrying the same run over and over
+/// while the winner has not recorded it yet.
+fn next_run(
+    config: &SchedulerConfig,
+    last_run_at: Option<DateTime<FixedOffset>>,
+    last_attempt: Option<DateTime<FixedOffset>>,
+    now: DateTime<FixedOffset>,
+) -> Option<DateTime<FixedOffset>> {
+    let since = std::cmp::max(last_run_at, last_attempt).unwrap_or(now);
+    let due = config.schedule.next_after(since)?;
+    if due > now {
+        return Some(due);
+    }
+
+    match config.missed_runs {
+        MissedRuns::RunOnce => Some(now),
+        MissedRuns::Skip => config.schedule.next_after(now),
+    }
+}
+
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return max;
+    }
+    Duration::from_millis(rand::random_range(0..=max.as_millis() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler_config(schedule: Schedule, missed_runs: MissedRuns) -> SchedulerConfig {
+        SchedulerConfig {
+            schedule,
+            jitter: Duration::ZERO,
+            missed_runs,
+            source: SourceConfig::default(),
+        }
+    }
+
+    fn time(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    #[test]
+    fn test_next_run() {
+        let now = time("2026-10-18T12:00:00Z");
+        let daily = Schedule::Cron(Box::new(cron::Schedule::from_str("0 0 4 * * *").unwrap()));
+
+        let config = scheduler_config(daily.clone(), MissedRuns::RunOnce);
+        assert_eq!(
+            next_run(&config, None, None, now),
+            Some(time("2026-10-19T04:00:00Z"))
+        );
+        assert_eq!(
+            next_run(&config, Some(time("2026-10-18T04:00:05Z")), None, now),
+            Some(time("2026-10-19T04:00:00Z"))
+        );
+        assert_eq!(
+            next_run(&config, Some(time("2026-10-16T04:00:05Z")), None, now),
+            Some(now)
+        );
+        assert_eq!(
+            next_run(
+                &config,
+                Some(time("2026-10-16T04:00:05Z")),
+                Some(now),
+                now + chrono::Duration::seconds(1)
+            ),
+            Some(time("2026-10-19T04:00:00Z"))
+        );
+
+        let config = scheduler_config(daily, MissedRuns::Skip);
+        assert_eq!(
+            next_run(&config, Some(time("2026-10-16T04:00:05Z")), None, now),
+            Some(time("2026-10-19T04:00:00Z"))
+        );
+
+        let hourly = Schedule::Interval(chrono::Duration::hours(1));
+        let config = scheduler_config(hourly, MissedRuns::RunOnce);
+        assert_eq!(
+            next_run(&config, Some(time("2026-10-18T11:30:00Z")), None, now),
+            Some(time("2026-10-18T12:30:00Z"))
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::csv_source::{CsvSource, CsvSourceConfig};
+use super::http_source::{HttpSource, HttpSourceConfig};
+
+/// One event with its organizer as provided by a source, still unvalidated.
+///
+/// Fields follow the columns of the original CSV export, the crawler parses and validates them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventRecord {
+    pub kind: String,
+    pub name: String,
+    pub shop: String,
+    pub street_address: String,
+    pub state: String,
+    pub city: String,
+    pub country_code: String,
+    pub pokemon_event_slug: String,
+    pub guid: String,
+    pub latitude: String,
+    pub longitude: String,
+    /// Local start time in the organizer's timezone, `%Y-%m-%d %H:%M:%S`.
+    pub happening_at: String,
+    pub league: String,
+    /// The input the record was read from, kept for the rejections of a crawl run.
+    pub raw: String,
+}
+
+/// A record the source could not read.
+#[derive(Debug)]
+pub struct RejectedRecord {
+    pub error: anyhow::Error,
+    /// Missing when the source failed before the input could be split into records.
+    pub raw: Option<String>,
+}
+
+impl From<anyhow::Error> for RejectedRecord {
+    fn from(error: anyhow::Error) -> Self {
+        Self { error, raw: None }
+    }
+}
+
+/// Why a source yielded no record.
+#[derive(Debug)]
+pub enum RecordError {
+    /// The crawler skips the record and carries on.
+    Rejected(RejectedRecord),
+    /// Part of the input could not be read, e.g. a page that kept failing, so the run fails
+    /// rather than silently missing its events.
+    Failed(anyhow::Error),
+}
+
+impl From<RejectedRecord> for RecordError {
+    fn from(rejected: RejectedRecord) -> Self {
+        Self::Rejected(rejected)
+    }
+}
+
+pub type EventRecords = BoxStream<'static, Result<EventRecord, RecordError>>;
+
+#[async_trait]
+pub trait EventSource: Send + Sync {
+    /// Identifies the source in logs.
+    fn name(&self) -> String;
+
+    /// Fails when the source cannot be read at all.
+    async fn records(&self) -> Result<EventRecords, anyhow::Error>;
+}
+
+/// Selects and configures the source of a crawler run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SourceConfig {
+    Csv(CsvSourceConfig),
+    Http(HttpSourceConfig),
+}
+
+impl Default for SourceConfig {
+    fn default() -> Self {
+        Self::Csv(CsvSourceConfig::default())
+    }
+}
+
+impl SourceConfig {
+    /// Fails when the configuration is not valid, e.g. a file outside of the data directory.
+    pub fn build(&self) -> Result<Box<dyn EventSource>, anyhow::Error> {
+        Ok(match self {
+            Self::Csv(config) => Box::new(CsvSource::new(CsvSourceConfig {
+                path: data_path(&data_dir(), &config.path)?,
+                ..config.clone()
+            })),
+            Self::Http(config) => Box::new(HttpSource::new(config.clone())?),
+        })
+    }
+}
+
+/// Directory the sources may access files in, `CRAWLER_DATA_DIR` or `data` by default.
+pub fn data_dir() -> PathBuf {
+    std::env::var("CRAWLER_DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("data"))
+}
+
+/// Resolves `path` relative to `data_dir` and makes sure that it is inside, source configurations
+/// come from API requests and must not reach other files.
+pub fn data_path(data_dir: &Path, path: &Path) -> Result<PathBuf, anyhow::Error> {
+    let data_dir = data_dir
+        .canonicalize()
+        .with_context(|| format!("data directory {} is not accessible", data_dir.display()))?;
+    let resolved = data_dir
+        .join(path)
+        .canonicalize()
+        .with_context(|| format!("{} is not accessible", path.display()))?;
+    if !resolved.starts_with(&data_dir) {
+        bail!(
+            "{} is outside of the data directory {}",
+            path.display(),
+            data_dir.display()
+        );
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_path() {
+        let root = std::env::temp_dir().join(format!("crawler-{}", uuid::Uuid::new_v4()));
+        let data_dir = root.join("data");
+        std::fs::create_dir_all(data_dir.join("regional")).unwrap();
+        std::fs::write(data_dir.join("events.csv"), "").unwrap();
+        std::fs::write(data_dir.join("regional/events.csv"), "").unwrap();
+        std::fs::write(root.join("secrets.csv"), "").unwrap();
+
+        let result = (|| {
+            assert_eq!(
+                data_path(&data_dir, &data_dir.join("regional/events.csv")).unwrap(),
+                data_dir.canonicalize()?.join("regional/events.csv")
+            );
+            assert!(data_path(&data_dir, &root.join("secrets.csv")).is_err());
+            assert!(data_path(&data_dir, &data_dir.join("../secrets.csv")).is_err());
+            assert!(data_path(&data_dir, Path::new("/etc/passwd")).is_err());
+            assert!(data_path(&data_dir, &data_dir.join("missing.csv")).is_err());
+
+            assert_eq!(
+                data_path(&data_dir, Path::new("regional/events.csv")).unwrap(),
+                data_dir.canonicalize()?.join("regional/events.csv")
+            );
+            assert!(data_path(&data_dir, Path::new("../secrets.csv")).is_err());
+            assert_eq!(
+                data_path(&data_dir, &CsvSourceConfig::default().path).unwrap(),
+                data_dir.canonicalize()?.join("events.csv")
+            );
+
+            #[cfg(unix)]
+            {
+                std::os::unix::fs::symlink(root.join("secrets.csv"), data_dir.join("link.csv"))?;
+                assert!(data_path(&data_dir, &data_dir.join("link.csv")).is_err());
+            }
+
+            Ok::<_, std::io::Error>(())
+        })();
+        std::fs::remove_dir_all(&root).unwrap();
+        result.unwrap();
+    }
+}
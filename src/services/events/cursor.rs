@@ -0,0 +1,74 @@
+use anyhow::Context;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::events;
+use crate::services::events::search_service::{EventsSortBy, SortDirection};
+
+/// Position of the last returned event for keyset pagination over `(<sort column>, id)`.
+///
+/// Handed out to clients as an opaque URL-safe string. Only timestamp sorts can be paged this
+/// way, see [`EventsCursor::after`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventsCursor {
+    pub sort_by: EventsSortBy,
+    pub direction: SortDirection,
+    pub value: DateTime<FixedOffset>,
+    pub id: i32,
+}
+
+impl EventsCursor {
+    pub fn after(
+        event: &events::Model,
+        sort_by: EventsSortBy,
+        direction: SortDirection,
+    ) -> Option<Self> {
+        let value = match sort_by {
+            EventsSortBy::HappeningAt => event.happening_at,
+            EventsSortBy::CreatedAt => event.created_at,
+            EventsSortBy::UpdatedAt => event.updated_at,
+            EventsSortBy::OrganizerName | EventsSortBy::Distance | EventsSortBy::Relevance => {
+                return None;
+            }
+        };
+
+        Some(EventsCursor {
+            sort_by,
+            direction,
+            value,
+            id: event.id,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursor is always serializable");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(value: &str) -> Result<Self, anyhow::Error> {
+        let json = URL_SAFE_NO_PAD
+            .decode(value.trim())
+            .context("cursor is not valid base64")?;
+        serde_json::from_slice(&json).context("cursor is malformed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = EventsCursor {
+            sort_by: EventsSortBy::CreatedAt,
+            direction: SortDirection::Desc,
+            value: DateTime::parse_from_rfc3339("2026-10-18T10:00:00+00:00").unwrap(),
+            id: 42,
+        };
+
+        assert_eq!(EventsCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(EventsCursor::decode("not a cursor").is_err());
+        assert!(EventsCursor::decode(&URL_SAFE_NO_PAD.encode("{}")).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::anyhow;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::Connections;
+use crate::entities::{events, organizers};
+use crate::error::ApiError;
+use crate::persistence::{events_repository, organizers_repository};
+use crate::services::events::presentation;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventDetails {
+    pub event: events::Model,
+    pub organizer: organizers::Model,
+    /// Event start in the organizer's timezone.
+    pub local_happening_at: DateTime<FixedOffset>,
+    /// Link to the event on the official event locator.
+    pub url: String,
+}
+
+pub async fn find(conns: &Connections, guid: Uuid) -> Result<EventDetails, ApiError> {
+    let event = events_repository::find_by_guid(&conns.db, guid)
+        .await?
+        .ok_or_else(|| ApiError::not_found(anyhow!("event {guid} not found")))?;
+    let organizer = organizers_repository::find_by_id(&conns.db, event.organizer_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(anyhow!("organizer of event {guid} not found")))?;
+
+    Ok(EventDetails {
+        local_happening_at: presentation::local_happening_at(&event, &organizer),
+        url: presentation::event_url(&event),
+        event,
+        organizer,
+    })
+}
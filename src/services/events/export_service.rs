@@ -0,0 +1,374 @@
+use anyhow::{Context, anyhow};
+use axum::body::Bytes;
+use futures_util::Stream;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::{events, organizers};
+use crate::error::ApiError;
+use crate::services::events::cursor::EventsCursor;
+use crate::services::events::search_service::{
+    self, EventsSearchFilters, EventsSort, EventsSortBy, SortDirection,
+};
+use crate::services::events::{presentation, text_search};
+
+const EXPORT_BATCH_SIZE: u64 = 500;
+
+/// Field names of [`EventExportRow`], written on their own for exports without rows.
+const CSV_HEADER: [&str; 19] = [
+    "type",
+    "name",
+    "shop",
+    "street_adress",
+    "state",
+    "city",
+    "country_code",
+    "pokemon_url",
+    "guid",
+    "latitude",
+    "longitude",
+    "when",
+    "league",
+    "event_id",
+    "organizer_id",
+    "timezone",
+    "happening_at",
+    "created_at",
+    "updated_at",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// `;`-delimited with the crawler's column names, so exports can be imported again.
+    #[default]
+    Csv,
+    /// One JSON object per line.
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventsExportRequest {
+    pub filters: EventsSearchFilters,
+    /// Free text matched like in the search.
+    pub query: Option<String>,
+    /// Only `happening_at`, `created_at` and `updated_at` are supported, events are exported by
+    /// id without it.
+    pub sort: Option<EventsSort>,
+}
+
+/// Event joined with its organizer, named like the columns of the crawler's CSV input.
+#[derive(Debug, Serialize)]
+struct EventExportRow {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    shop: String,
+    #[serde(rename = "street_adress")]
+    street_address: String,
+    state: String,
+    city: String,
+    country_code: String,
+    #[serde(rename = "pokemon_url")]
+    pokemon_event_slug: String,
+    guid: String,
+    latitude: f64,
+    longitude: f64,
+    /// Local start time in the organizer's timezone, as read by the crawler.
+    #[serde(rename = "when")]
+    local_happening_at: String,
+    league: Option<i32>,
+    event_id: i32,
+    organizer_id: i32,
+    timezone: String,
+    happening_at: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl EventExportRow {
+    fn new(event: events::Model, organizer: organizers::Model) -> Self {
+        let local_happening_at = presentation::local_happening_at(&event, &organizer)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        EventExportRow {
+            kind: event.kind,
+            name: event.name,
+            shop: organizer.name,
+            street_address: organizer.address,
+            state: organizer.area,
+            city: organizer.city,
+            country_code: organizer.country,
+            pokemon_event_slug: event.pokemon_event_slug,
+            guid: event.guid.to_string(),
+            latitude: organizer.latitude,
+            longitude: organizer.longitude,
+            local_happening_at,
+            league: event.league,
+            event_id: event.id,
+            organizer_id: organizer.id,
+            timezone: organizer.timezone,
+            happening_at: event.happening_at.to_rfc3339(),
+            created_at: event.created_at.to_rfc3339(),
+            updated_at: event.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+struct ExportState {
+    db: DatabaseConnection,
+    query: Select<events::Entity>,
+    format: ExportFormat,
+    sort: Option<(EventsSortBy, SortDirection)>,
+    /// The last exported event, the next batch continues after it.
+    last: Option<events::Model>,
+    done: bool,
+}
+
+/// Streams every event matching the request's filters and query.
+///
+/// Events are fetched in batches so that the export never holds more than one batch in memory.
+pub fn export(
+    conns: &Connections,
+    request: EventsExportRequest,
+    format: ExportFormat,
+) -> Result<impl Stream<Item = Result<Bytes, anyhow::Error>> + use<>, ApiError> {
+    let sort = export_sort(request.sort.as_ref(), &request.filters)?;
+    let tsquery = request
+        .query
+        .as_deref()
+        .and_then(text_search::prefix_tsquery);
+    let query = search_service::filter_query(request.filters)?
+        .apply_if(tsquery.as_deref(), |query, tsquery| {
+            query.filter(text_search::matches_expr(tsquery))
+        });
+
+    let state = ExportState {
+        db: conns.db.clone(),
+        query,
+        format,
+        sort,
+        last: None,
+        done: false,
+    };
+
+    Ok(futures_util::stream::unfold(
+        state,
+        |mut state| async move {
+            if state.done {
+                return None;
+            }
+            match next_batch(&mut state).await {
+                Ok(Some(bytes)) => Some((Ok(bytes), state)),
+                Ok(None) => None,
+                Err(err) => {
+                    state.done = true;
+                    Some((Err(err), state))
+                }
+            }
+        },
+    ))
+}
+
+async fn next_batch(state: &mut ExportState) -> Result<Option<Bytes>, anyhow::Error> {
+    let query = match state.sort {
+        None => state
+            .query
+            .clone()
+            .apply_if(state.last.as_ref(), |query, last| {
+                query.filter(events::Column::Id.gt(last.id))
+            })
+            .order_by(events::Column::Id, Order::Asc),
+        Some((sort_by, direction)) => state
+            .query
+            .clone()
+            .apply_if(
+                state
+                    .last
+                    .as_ref()
+                    .and_then(|last| EventsCursor::after(last, sort_by, direction)),
+                |query, cursor| query.filter(search_service::keyset_expr(&cursor)),
+            )
+            .order_by(sort_column(sort_by), direction.into())
+            .order_by(events::Column::Id, direction.into()),
+    };
+    let rows = query
+        .limit(EXPORT_BATCH_SIZE)
+        .select_also(organizers::Entity)
+        .all(&state.db)
+        .await
+        .context("failed to load events for export")?;
+
+    if (rows.len() as u64) < EXPORT_BATCH_SIZE {
+        state.done = true;
+    }
+    let is_first_batch = state.last.is_none();
+    let Some((last_event, _)) = rows.last() else {
+        // An empty CSV export still gets its header row.
+        return match (is_first_batch, state.format) {
+            (true, ExportFormat::Csv) => Ok(Some(Bytes::from(encode_csv(vec![], true)?))),
+            _ => Ok(None),
+        };
+    };
+    state.last = Some(last_event.clone());
+
+    let rows: Vec<EventExportRow> = rows
+        .into_iter()
+        .filter_map(|(event, organizer)| {
+            organizer.map(|organizer| EventExportRow::new(event, organizer))
+        })
+        .collect();
+
+    let bytes = match state.format {
+        ExportFormat::Csv => encode_csv(rows, is_first_batch)?,
+        ExportFormat::Ndjson => encode_ndjson(rows)?,
+    };
+
+    Ok(Some(Bytes::from(bytes)))
+}
+
+/// Exports are paged by keyset, so only timestamp sorts are supported, see [`EventsCursor`].
+fn export_sort(
+    sort: Option<&EventsSort>,
+    filters: &EventsSearchFilters,
+) -> Result<Option<(EventsSortBy, SortDirection)>, ApiError> {
+    let Some(sort) = sort else {
+        return Ok(None);
+    };
+    if !matches!(
+        sort.by,
+        EventsSortBy::HappeningAt | EventsSortBy::CreatedAt | EventsSortBy::UpdatedAt
+    ) {
+        return Err(ApiError::bad_request(anyhow!(
+            "exports can only be sorted by happening_at, created_at or updated_at"
+        )));
+    }
+    let direction = sort
+        .direction
+        .unwrap_or_else(|| search_service::default_direction(sort.by, filters.state));
+
+    Ok(Some((sort.by, direction)))
+}
+
+fn sort_column(sort_by: EventsSortBy) -> events::Column {
+    match sort_by {
+        EventsSortBy::CreatedAt => events::Column::CreatedAt,
+        EventsSortBy::UpdatedAt => events::Column::UpdatedAt,
+        _ => events::Column::HappeningAt,
+    }
+}
+
+fn encode_csv(rows: Vec<EventExportRow>, with_header: bool) -> Result<Vec<u8>, anyhow::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b';')
+        .has_headers(with_header)
+        .from_writer(Vec::new());
+
+    if rows.is_empty() && with_header {
+        writer.write_record(CSV_HEADER)?;
+    }
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    writer.into_inner().context("failed to flush CSV export")
+}
+
+fn encode_ndjson(rows: Vec<EventExportRow>) -> Result<Vec<u8>, anyhow::Error> {
+    let mut bytes = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut bytes, &row)?;
+        bytes.push(b'\n');
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> EventExportRow {
+        EventExportRow {
+            kind: "League Cup".to_string(),
+            name: "Cup; Köln".to_string(),
+            shop: "Cards".to_string(),
+            street_address: "Main 1".to_string(),
+            state: "NRW".to_string(),
+            city: "Köln".to_string(),
+            country_code: "DE".to_string(),
+            pokemon_event_slug: "cup-koln".to_string(),
+            guid: "7b0f6c44-2f6a-4a4a-9c55-5b8f3d3d1e11".to_string(),
+            latitude: 50.9,
+            longitude: 6.9,
+            local_happening_at: "2026-10-18 10:00:00".to_string(),
+            league: None,
+            event_id: 1,
+            organizer_id: 2,
+            timezone: "Europe/Berlin".to_string(),
+            happening_at: "2026-10-18T08:00:00+00:00".to_string(),
+            created_at: "2026-10-01T08:00:00+00:00".to_string(),
+            updated_at: "2026-10-01T08:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_export_request() {
+        let request: EventsExportRequest = serde_json::from_value(serde_json::json!({
+            "filters": { "countries": ["DE"] },
+            "sort": { "by": "created_at" },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            export_sort(request.sort.as_ref(), &request.filters).unwrap(),
+            Some((EventsSortBy::CreatedAt, SortDirection::Desc))
+        );
+        assert_eq!(export_sort(None, &request.filters).unwrap(), None);
+
+        let relevance = EventsSort {
+            by: EventsSortBy::Relevance,
+            direction: None,
+        };
+        assert!(export_sort(Some(&relevance), &request.filters).is_err());
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        let csv = String::from_utf8(encode_csv(vec![row()], true).unwrap()).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some(CSV_HEADER.join(";").as_str()));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .starts_with(r#"League Cup;"Cup; Köln";Cards;"#)
+        );
+        assert_eq!(lines.next(), None);
+
+        let empty = String::from_utf8(encode_csv(vec![], true).unwrap()).unwrap();
+        assert_eq!(empty.trim_end(), CSV_HEADER.join(";"));
+
+        let without_header = String::from_utf8(encode_csv(vec![row()], false).unwrap()).unwrap();
+        assert_eq!(without_header.lines().count(), 1);
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::events::EventKind;
+use crate::error::ApiError;
+use crate::persistence::events_repository::{self, LocationCount};
+use crate::services::events::search_service::{non_empty, upcoming_cutoff};
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct EventsFacetsRequest {
+    /// Only count events of these kinds.
+    pub kinds: Option<Vec<EventKind>>,
+}
+
+/// Upcoming events per location. Values match `countries`, `areas` and `cities` of
+/// `EventsSearchFilters` exactly.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventsFacetsResponse {
+    pub countries: Vec<CountryFacet>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CountryFacet {
+    pub country: String,
+    pub upcoming_events: u64,
+    pub areas: Vec<AreaFacet>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AreaFacet {
+    pub area: String,
+    pub upcoming_events: u64,
+    pub cities: Vec<CityFacet>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CityFacet {
+    pub city: String,
+    pub upcoming_events: u64,
+}
+
+pub async fn facets(
+    conns: &Connections,
+    request: EventsFacetsRequest,
+) -> Result<EventsFacetsResponse, ApiError> {
+    let counts =
+        events_repository::location_counts(&conns.db, non_empty(request.kinds), upcoming_cutoff())
+            .await?;
+
+    Ok(EventsFacetsResponse {
+        countries: build_tree(counts),
+    })
+}
+
+fn build_tree(counts: Vec<LocationCount>) -> Vec<CountryFacet> {
+    let mut tree: BTreeMap<String, BTreeMap<String, BTreeMap<String, u64>>> = BTreeMap::new();
+    for count in counts {
+        *tree
+            .entry(count.country)
+            .or_default()
+            .entry(count.area)
+            .or_default()
+            .entry(count.city)
+            .or_default() += count.total_events as u64;
+    }
+
+    tree.into_iter()
+        .map(|(country, areas)| {
+            let areas: Vec<AreaFacet> = areas
+                .into_iter()
+                .map(|(area, cities)| {
+                    let cities: Vec<CityFacet> = cities
+                        .into_iter()
+                        .map(|(city, upcoming_events)| CityFacet {
+                            city,
+                            upcoming_events,
+                        })
+                        .collect();
+                    AreaFacet {
+                        area,
+                        upcoming_events: cities.iter().map(|city| city.upcoming_events).sum(),
+                        cities,
+                    }
+                })
+                .collect();
+            CountryFacet {
+                country,
+                upcoming_events: areas.iter().map(|area| area.upcoming_events).sum(),
+                areas,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(country: &str, area: &str, city: &str, total_events: i64) -> LocationCount {
+        LocationCount {
+            country: country.to_string(),
+            area: area.to_string(),
+            city: city.to_string(),
+            total_events,
+        }
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let tree = build_tree(vec![
+            count("DE", "Bavaria", "Munich", 5),
+            count("AT", "Vienna", "Vienna", 2),
+            count("DE", "Bavaria", "Augsburg", 1),
+            count("DE", "Berlin", "Berlin", 3),
+        ]);
+
+        assert_eq!(
+            tree,
+            vec![
+                CountryFacet {
+                    country: "AT".to_string(),
+                    upcoming_events: 2,
+                    areas: vec![AreaFacet {
+                        area: "Vienna".to_string(),
+                        upcoming_events: 2,
+                        cities: vec![CityFacet {
+                            city: "Vienna".to_string(),
+                            upcoming_events: 2,
+                        }],
+                    }],
+                },
+                CountryFacet {
+                    country: "DE".to_string(),
+                    upcoming_events: 9,
+                    areas: vec![
+                        AreaFacet {
+                            area: "Bavaria".to_string(),
+                            upcoming_events: 6,
+                            cities: vec![
+                                CityFacet {
+                                    city: "Augsburg".to_string(),
+                                    upcoming_events: 1,
+                                },
+                                CityFacet {
+                                    city: "Munich".to_string(),
+                                    upcoming_events: 5,
+                                },
+                            ],
+                        },
+                        AreaFacet {
+                            area: "Berlin".to_string(),
+                            upcoming_events: 3,
+                            cities: vec![CityFacet {
+                                city: "Berlin".to_string(),
+                                upcoming_events: 3,
+                            }],
+                        },
+                    ],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::Context;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::events::EventKind;
+use crate::services::events::search_service::EventsSearchFilters;
+
+/// Filters of the GET feed endpoints, which cannot take a JSON body.
+///
+/// `filters` carries a complete `EventsSearchFilters` as a token, the remaining parameters are
+/// comma-separated shortcuts for the most common filters and replace the respective token
+/// values.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedQuery {
+    /// Token returned by `POST /events/feeds/token`.
+    pub filters: Option<String>,
+    /// Comma-separated country codes, e.g. `DE,AT`.
+    pub countries: Option<String>,
+    /// Comma-separated areas.
+    pub areas: Option<String>,
+    /// Comma-separated cities.
+    pub cities: Option<String>,
+    /// Comma-separated event kinds, e.g. `League Cup,League Challenge`.
+    pub kinds: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FeedToken {
+    /// Pass as the `filters` query parameter of the feed endpoints.
+    pub token: String,
+}
+
+impl FeedQuery {
+    pub fn into_filters(self) -> Result<EventsSearchFilters, anyhow::Error> {
+        let mut filters = match self.filters.as_deref() {
+            Some(token) => decode_token(token)?,
+            None => EventsSearchFilters::default(),
+        };
+
+        if let Some(countries) = self.countries.as_deref() {
+            filters.countries = Some(split_list(countries));
+        }
+        if let Some(areas) = self.areas.as_deref() {
+            filters.areas = Some(split_list(areas));
+        }
+        if let Some(cities) = self.cities.as_deref() {
+            filters.cities = Some(split_list(cities));
+        }
+        if let Some(kinds) = self.kinds.as_deref() {
+            filters.kinds = Some(
+                split_list(kinds)
+                    .iter()
+                    .map(|kind| EventKind::from_str(kind).expect("EventKind parsing is infallible"))
+                    .collect(),
+            );
+        }
+
+        Ok(filters)
+    }
+}
+
+pub fn encode_token(filters: &EventsSearchFilters) -> String {
+    let json = serde_json::to_vec(filters).expect("filters are always serializable");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode_token(token: &str) -> Result<EventsSearchFilters, anyhow::Error> {
+    let json = URL_SAFE_NO_PAD
+        .decode(token.trim())
+        .context("filters token is not valid base64")?;
+    serde_json::from_slice(&json).context("filters token is malformed")
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_filters() {
+        let token = encode_token(&EventsSearchFilters {
+            countries: Some(vec!["CZ".to_string()]),
+            areas: Some(vec!["Prague".to_string()]),
+            ..Default::default()
+        });
+
+        let filters = FeedQuery {
+            filters: Some(token),
+            countries: Some("DE, AT,".to_string()),
+            kinds: Some("League Cup,League Cup VG".to_string()),
+            ..Default::default()
+        }
+        .into_filters()
+        .unwrap();
+
+        assert_eq!(
+            filters.countries,
+            Some(vec!["DE".to_string(), "AT".to_string()])
+        );
+        assert_eq!(filters.areas, Some(vec!["Prague".to_string()]));
+        assert_eq!(
+            filters.kinds,
+            Some(vec![EventKind::LeagueCup, EventKind::LeagueCupVG])
+        );
+
+        assert!(
+            FeedQuery {
+                filters: Some("???".to_string()),
+                ..Default::default()
+            }
+            .into_filters()
+            .is_err()
+        );
+    }
+}
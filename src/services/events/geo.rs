@@ -0,0 +1,45 @@
+use sea_orm::sea_query::Expr;
+
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two coordinates using the haversine formula.
+pub fn distance_km(latitude_a: f64, longitude_a: f64, latitude_b: f64, longitude_b: f64) -> f64 {
+    let d_latitude = (latitude_b - latitude_a).to_radians();
+    let d_longitude = (longitude_b - longitude_a).to_radians();
+    let a = (d_latitude / 2.0).sin().powi(2)
+        + latitude_a.to_radians().cos()
+            * latitude_b.to_radians().cos()
+            * (d_longitude / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+/// SQL counterpart of [`distance_km`] measured from the given point to the joined organizer.
+pub fn organizer_distance_km_expr(latitude: f64, longitude: f64) -> Expr {
+    Expr::cust_with_values(
+        r#"2 * $3 * asin(least(1, sqrt(
+            power(sin(radians("organizers"."latitude" - $1) / 2), 2)
+            + cos(radians($1)) * cos(radians("organizers"."latitude"))
+            * power(sin(radians("organizers"."longitude" - $2) / 2), 2)
+        )))"#,
+        [latitude, longitude, EARTH_RADIUS_KM],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_km() {
+        assert_eq!(distance_km(50.94, 6.96, 50.94, 6.96), 0.0);
+
+        // Cologne -> Berlin
+        let distance = distance_km(50.9375, 6.9603, 52.5200, 13.4050);
+        assert!((distance - 477.0).abs() < 2.0, "got {distance}");
+
+        // Symmetric across the antimeridian
+        let distance = distance_km(0.0, 179.5, 0.0, -179.5);
+        assert!((distance - 111.2).abs() < 0.5, "got {distance}");
+    }
+}
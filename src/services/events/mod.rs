@@ -1,2 +1,12 @@
+pub mod calendar_service;
 pub mod crawler;
+pub mod cursor;
+pub mod details_service;
+pub mod export_service;
+pub mod facets_service;
+pub mod feed_filters;
+pub mod geo;
+pub mod news_feed_service;
+pub mod presentation;
 pub mod search_service;
+pub mod text_search;
@@ -0,0 +1,162 @@
+use chrono::Utc;
+use sea_orm::*;
+
+use crate::Connections;
+use crate::entities::{events, organizers};
+use crate::error::ApiError;
+use crate::services::events::presentation;
+use crate::services::events::search_service::{self, EventsSearchFilters};
+
+const MAX_FEED_ENTRIES: u64 = 100;
+
+const FEED_TITLE: &str = "Newly announced Pokémon TCG events";
+
+const FEED_ID: &str = "urn:poketcgevents:events:new";
+
+/// Atom 1.0 feed of the most recently discovered events matching `filters`.
+pub async fn atom(conns: &Connections, filters: EventsSearchFilters) -> Result<String, ApiError> {
+    let rows = newest(conns, filters).await?;
+    let updated = rows
+        .iter()
+        .map(|(event, _)| event.updated_at)
+        .max()
+        .unwrap_or_else(|| Utc::now().fixed_offset());
+
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    xml.push_str(&format!("<id>{FEED_ID}</id>"));
+    xml.push_str(&format!("<title>{}</title>", escape_xml(FEED_TITLE)));
+    xml.push_str(&format!("<updated>{}</updated>", updated.to_rfc3339()));
+    for (event, organizer) in &rows {
+        let url = escape_xml(&presentation::event_url(event));
+        xml.push_str("<entry>");
+        xml.push_str(&format!("<id>urn:uuid:{}</id>", event.guid));
+        xml.push_str(&format!("<title>{}</title>", escape_xml(&event.name)));
+        xml.push_str(&format!(r#"<link rel="alternate" href="{url}"/>"#));
+        xml.push_str(&format!(
+            "<published>{}</published>",
+            event.created_at.to_rfc3339()
+        ));
+        xml.push_str(&format!(
+            "<updated>{}</updated>",
+            event.updated_at.to_rfc3339()
+        ));
+        xml.push_str(&format!(
+            "<author><name>{}</name></author>",
+            escape_xml(&organizer.name)
+        ));
+        xml.push_str(&format!(
+            "<summary>{}</summary>",
+            escape_xml(&summary(event, organizer))
+        ));
+        xml.push_str("</entry>");
+    }
+    xml.push_str("</feed>");
+
+    Ok(xml)
+}
+
+/// RSS 2.0 feed of the most recently discovered events matching `filters`.
+pub async fn rss(conns: &Connections, filters: EventsSearchFilters) -> Result<String, ApiError> {
+    let rows = newest(conns, filters).await?;
+
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.push_str(r#"<rss version="2.0"><channel>"#);
+    xml.push_str(&format!("<title>{}</title>", escape_xml(FEED_TITLE)));
+    xml.push_str(&format!(
+        "<link>{}</link>",
+        escape_xml(&presentation::pokemon_events_url())
+    ));
+    xml.push_str(&format!(
+        "<description>{}</description>",
+        escape_xml(FEED_TITLE)
+    ));
+    if let Some((event, _)) = rows.first() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>",
+            event.created_at.to_rfc2822()
+        ));
+    }
+    for (event, organizer) in &rows {
+        xml.push_str("<item>");
+        xml.push_str(&format!(
+            r#"<guid isPermaLink="false">{}</guid>"#,
+            event.guid
+        ));
+        xml.push_str(&format!("<title>{}</title>", escape_xml(&event.name)));
+        xml.push_str(&format!(
+            "<link>{}</link>",
+            escape_xml(&presentation::event_url(event))
+        ));
+        xml.push_str(&format!(
+            "<pubDate>{}</pubDate>",
+            event.created_at.to_rfc2822()
+        ));
+        xml.push_str(&format!(
+            "<description>{}</description>",
+            escape_xml(&summary(event, organizer))
+        ));
+        xml.push_str("</item>");
+    }
+    xml.push_str("</channel></rss>");
+
+    Ok(xml)
+}
+
+async fn newest(
+    conns: &Connections,
+    filters: EventsSearchFilters,
+) -> Result<Vec<(events::Model, organizers::Model)>, ApiError> {
+    let rows = search_service::filter_query(filters)?
+        .order_by(events::Column::CreatedAt, Order::Desc)
+        .order_by(events::Column::Id, Order::Desc)
+        .limit(MAX_FEED_ENTRIES)
+        .select_also(organizers::Entity)
+        .all(&conns.db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(event, organizer)| organizer.map(|organizer| (event, organizer)))
+        .collect())
+}
+
+fn summary(event: &events::Model, organizer: &organizers::Model) -> String {
+    let local_happening_at = presentation::local_happening_at(event, organizer);
+    format!(
+        "{} at {}, {}, {} on {}",
+        event.kind,
+        organizer.name,
+        organizer.city,
+        organizer.country,
+        local_happening_at.format("%Y-%m-%d %H:%M %:z")
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"Cards & <Co> "Köln's""#),
+            "Cards &amp; &lt;Co&gt; &quot;Köln&apos;s&quot;"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
+use url::Url;
+
+use crate::entities::{events, organizers};
+
+const DEFAULT_POKEMON_EVENTS_URL: &str = "https://events.pokemon.com/";
+
+/// Event start in the organizer's timezone, falling back to UTC for unknown timezones.
+pub fn local_happening_at(
+    event: &events::Model,
+    organizer: &organizers::Model,
+) -> DateTime<FixedOffset> {
+    match organizer.timezone.parse::<Tz>() {
+        Ok(tz) => event.happening_at.with_timezone(&tz).fixed_offset(),
+        Err(_) => event.happening_at.with_timezone(&Utc).fixed_offset(),
+    }
+}
+
+/// Canonical link to the event on the official event locator.
+///
+/// `pokemon_event_slug` is resolved against `POKEMON_EVENTS_URL`, so both relative slugs and
+/// absolute URLs coming from the crawler end up as a full link.
+pub fn event_url(event: &events::Model) -> String {
+    let base = pokemon_events_base_url();
+
+    base.join(event.pokemon_event_slug.trim())
+        .map(String::from)
+        .unwrap_or_else(|_| base.to_string())
+}
+
+/// Home of the official event locator.
+pub fn pokemon_events_url() -> String {
+    pokemon_events_base_url().to_string()
+}
+
+fn pokemon_events_base_url() -> Url {
+    std::env::var("POKEMON_EVENTS_URL")
+        .ok()
+        .and_then(|value| Url::parse(&value).ok())
+        .unwrap_or_else(|| Url::parse(DEFAULT_POKEMON_EVENTS_URL).expect("valid default URL"))
+}
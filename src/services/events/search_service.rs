@@ -1,6 +1,8 @@
-use chrono::{Duration, Utc};
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Utc};
+use sea_orm::sea_query::Expr;
 use sea_orm::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 
 use crate::Connections;
@@ -8,100 +10,271 @@ use crate::entities::events::EventKind;
 use crate::entities::{events, organizers};
 use crate::error::ApiError;
 use crate::persistence::organizers_repository;
+use crate::services::events::cursor::EventsCursor;
+use crate::services::events::{geo, text_search};
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// Events stay "upcoming" for a while after their start so that ongoing ones are still listed.
+const UPCOMING_GRACE_PERIOD_HOURS: i64 = 8;
+
+/// Event start rendered as a local timestamp in the organizer's timezone.
+const LOCAL_HAPPENING_AT_SQL: &str =
+    r#"("events"."happening_at" AT TIME ZONE "organizers"."timezone")"#;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct EventsSearchFilters {
-    pub country: Option<String>,
-    pub city: Option<String>,
-    pub area: Option<String>,
-    pub organizer_id: Option<i32>,
-    pub kind: Option<EventKind>,
+    /// Also accepted as a single `country` as sent by older clients, likewise for `city`,
+    /// `area`, `organizer_id` and `kind`.
+    #[serde(default, alias = "country", deserialize_with = "one_or_many")]
+    pub countries: Option<Vec<String>>,
+    pub exclude_countries: Option<Vec<String>>,
+    #[serde(default, alias = "city", deserialize_with = "one_or_many")]
+    pub cities: Option<Vec<String>>,
+    pub exclude_cities: Option<Vec<String>>,
+    #[serde(default, alias = "area", deserialize_with = "one_or_many")]
+    pub areas: Option<Vec<String>>,
+    pub exclude_areas: Option<Vec<String>>,
+    #[serde(default, alias = "organizer_id", deserialize_with = "one_or_many")]
+    pub organizer_ids: Option<Vec<i32>>,
+    pub exclude_organizer_ids: Option<Vec<i32>>,
+    #[serde(default, alias = "kind", deserialize_with = "one_or_many")]
+    pub kinds: Option<Vec<EventKind>>,
+    pub exclude_kinds: Option<Vec<EventKind>>,
     pub state: Option<EventState>,
+    pub near: Option<GeoFilter>,
+    pub happening_from: Option<DateTime<FixedOffset>>,
+    pub happening_to: Option<DateTime<FixedOffset>>,
+    /// Days of the week in the organizer's local timezone.
+    pub weekdays: Option<Vec<Weekday>>,
+    /// Earliest local start time (inclusive) in the organizer's timezone.
+    #[schema(value_type = Option<String>, example = "10:00:00")]
+    pub local_time_from: Option<NaiveTime>,
+    /// Latest local start time (inclusive) in the organizer's timezone. A value before
+    /// `local_time_from` makes the window wrap around midnight.
+    #[schema(value_type = Option<String>, example = "18:00:00")]
+    pub local_time_to: Option<NaiveTime>,
 }
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+/// Matches events whose organizer is within `radius_km` of the given coordinate.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoFilter {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EventState {
     Upcoming,
     Past,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// ISO 8601 day number as returned by Postgres `EXTRACT(ISODOW ...)`.
+    fn iso_number(self) -> i32 {
+        match self {
+            Self::Monday => 1,
+            Self::Tuesday => 2,
+            Self::Wednesday => 3,
+            Self::Thursday => 4,
+            Self::Friday => 5,
+            Self::Saturday => 6,
+            Self::Sunday => 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventsSortBy {
+    HappeningAt,
+    /// When the crawler first saw the event, i.e. "newly announced".
+    CreatedAt,
+    UpdatedAt,
+    OrganizerName,
+    /// Requires `filters.near`.
+    Distance,
+    /// Requires `query`.
+    Relevance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl From<SortDirection> for Order {
+    fn from(direction: SortDirection) -> Self {
+        match direction {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        }
+    }
+}
+
+/// Results are always ordered by `id` in the same direction after the sort key.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EventsSort {
+    pub by: EventsSortBy,
+    /// Defaults to descending for `created_at`, `updated_at` and `relevance`, to descending
+    /// `happening_at` for past events, and to ascending otherwise.
+    pub direction: Option<SortDirection>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EventsSearchRequest {
     pub filters: EventsSearchFilters,
+    /// Free text matched against event and organizer names, cities and addresses, ignoring
+    /// case and accents. Results are ranked by relevance unless `sort` says otherwise.
+    pub query: Option<String>,
+    /// Defaults to `relevance` with a `query`, otherwise to `happening_at`.
+    pub sort: Option<EventsSort>,
+    /// Ignored when `cursor` is set.
+    #[serde(default)]
     pub page: u64,
     pub page_size: u64,
+    /// `next_cursor` of the previous response. Pages continue after the last seen
+    /// `(<sort key>, id)` so they stay stable while events are being imported.
+    pub cursor: Option<String>,
+    /// Set to `false` to skip counting all matching events.
+    pub include_total: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EventsSearchResponse {
     pub events: Vec<EventFull>,
-    pub total: u64,
+    pub total: Option<u64>,
     pub page: u64,
     pub page_size: u64,
+    /// Present when more events follow. Only returned when sorting by `happening_at`,
+    /// `created_at` or `updated_at`.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct EventFull {
     pub event: events::Model,
     pub organizer: organizers::Model,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
 }
 
 pub async fn search(
     conns: &Connections,
     request: EventsSearchRequest,
 ) -> Result<EventsSearchResponse, ApiError> {
-    let EventsSearchFilters {
-        city,
-        area,
-        kind,
-        country,
-        organizer_id,
-        state,
-    } = request.filters;
+    let state = request.filters.state;
+    let near = request.filters.near.clone();
     let page = Ord::max(request.page, 1);
     let page_size = Ord::min(request.page_size, 100);
 
-    let query = events::Entity::find()
-        .join(JoinType::InnerJoin, events::Relation::Organizers.def())
-        .apply_if(country, |query, country| {
-            query.filter(organizers::Column::Country.eq(country))
-        })
-        .apply_if(organizer_id, |query, organizer_id| {
-            query.filter(events::Column::OrganizerId.eq(organizer_id))
-        })
-        .apply_if(city, |query, city| {
-            query.filter(organizers::Column::City.eq(city))
-        })
-        .apply_if(area, |query, area| {
-            query.filter(organizers::Column::Area.eq(area))
-        })
-        .apply_if(kind, |query, kind| {
-            query.filter(events::Column::Kind.eq(kind))
+    let tsquery = request
+        .query
+        .as_deref()
+        .and_then(text_search::prefix_tsquery);
+
+    let sort_by = match &request.sort {
+        Some(sort) => sort.by,
+        None if tsquery.is_some() => EventsSortBy::Relevance,
+        None => EventsSortBy::HappeningAt,
+    };
+    let direction = request
+        .sort
+        .as_ref()
+        .and_then(|sort| sort.direction)
+        .unwrap_or_else(|| default_direction(sort_by, state));
+    let sort_expr = match sort_by {
+        EventsSortBy::HappeningAt => Expr::col((events::Entity, events::Column::HappeningAt)),
+        EventsSortBy::CreatedAt => Expr::col((events::Entity, events::Column::CreatedAt)),
+        EventsSortBy::UpdatedAt => Expr::col((events::Entity, events::Column::UpdatedAt)),
+        EventsSortBy::OrganizerName => Expr::col((organizers::Entity, organizers::Column::Name)),
+        EventsSortBy::Distance => {
+            let near = near.as_ref().ok_or_else(|| {
+                ApiError::bad_request(anyhow!("sorting by distance requires filters.near"))
+            })?;
+            geo::organizer_distance_km_expr(near.latitude, near.longitude)
+        }
+        EventsSortBy::Relevance => {
+            let tsquery = tsquery.as_deref().ok_or_else(|| {
+                ApiError::bad_request(anyhow!("sorting by relevance requires a query"))
+            })?;
+            text_search::rank_expr(tsquery)
+        }
+    };
+
+    let cursor = match request.cursor.as_deref() {
+        None => None,
+        Some(cursor) => {
+            let cursor = EventsCursor::decode(cursor).map_err(ApiError::bad_request)?;
+            if !matches!(
+                sort_by,
+                EventsSortBy::HappeningAt | EventsSortBy::CreatedAt | EventsSortBy::UpdatedAt
+            ) {
+                return Err(ApiError::bad_request(anyhow!(
+                    "cursor is only supported when sorting by happening_at, created_at or updated_at"
+                )));
+            }
+            if cursor.sort_by != sort_by || cursor.direction != direction {
+                return Err(ApiError::bad_request(anyhow!(
+                    "cursor was issued for a different sort"
+                )));
+            }
+            Some(cursor)
+        }
+    };
+
+    let query = filter_query(request.filters)?
+        .apply_if(tsquery.as_deref(), |query, tsquery| {
+            query.filter(text_search::matches_expr(tsquery))
         })
-        .apply_if(state, |query, state| match state {
-            EventState::Upcoming => query
-                .filter(events::Column::HappeningAt.gt(Utc::now() - Duration::hours(8)))
-                .order_by(events::Column::HappeningAt, Order::Asc),
-            EventState::Past => query
-                .filter(events::Column::HappeningAt.lt(Utc::now() - Duration::hours(8)))
-                .order_by(events::Column::HappeningAt, Order::Desc),
-        });
+        .order_by(sort_expr, direction.into())
+        .order_by(events::Column::Id, direction.into());
 
-    let events = query
+    let mut events = query
         .clone()
-        .limit(Some(page_size))
-        .offset(page.saturating_sub(1) * page_size)
+        .apply_if(cursor.as_ref(), |query, cursor| {
+            query.filter(keyset_expr(cursor))
+        })
+        .limit(Some(page_size + 1))
+        .offset(if cursor.is_some() {
+            0
+        } else {
+            page.saturating_sub(1) * page_size
+        })
         .all(&conns.db)
         .await?;
+    let has_more = events.len() as u64 > page_size;
+    events.truncate(page_size as usize);
+
+    let next_cursor = events
+        .last()
+        .filter(|_| has_more)
+        .and_then(|last| EventsCursor::after(last, sort_by, direction))
+        .map(|cursor| cursor.encode());
 
     let organizer_ids: Vec<i32> = events.iter().map(|event| event.organizer_id).collect();
     let organizers_map = organizers_repository::map_by_ids(&conns.db, organizer_ids).await?;
 
-    let total = if page <= 1 && (events.len() as u64) < page_size {
-        events.len() as u64
+    let total = if !request.include_total.unwrap_or(true) {
+        None
+    } else if page <= 1 && cursor.is_none() && !has_more {
+        Some(events.len() as u64)
     } else {
-        query.count(&conns.db).await?
+        Some(query.count(&conns.db).await?)
     };
 
     Ok(EventsSearchResponse {
@@ -111,6 +284,14 @@ pub async fn search(
                 organizers_map
                     .get(&event.organizer_id)
                     .map(|organizer| EventFull {
+                        distance_km: near.as_ref().map(|near| {
+                            geo::distance_km(
+                                near.latitude,
+                                near.longitude,
+                                organizer.latitude,
+                                organizer.longitude,
+                            )
+                        }),
                         event,
                         organizer: organizer.clone(),
                     })
@@ -119,5 +300,260 @@ pub async fn search(
         total,
         page,
         page_size,
+        next_cursor,
     })
 }
+
+/// Events joined with their organizer and narrowed down by `filters`, without any ordering.
+pub fn filter_query(filters: EventsSearchFilters) -> Result<Select<events::Entity>, ApiError> {
+    let EventsSearchFilters {
+        countries,
+        exclude_countries,
+        cities,
+        exclude_cities,
+        areas,
+        exclude_areas,
+        organizer_ids,
+        exclude_organizer_ids,
+        kinds,
+        exclude_kinds,
+        state,
+        near,
+        happening_from,
+        happening_to,
+        weekdays,
+        local_time_from,
+        local_time_to,
+    } = filters;
+
+    if let Some(near) = &near {
+        validate_geo_filter(near)?;
+    }
+    if let (Some(from), Some(to)) = (happening_from, happening_to)
+        && from > to
+    {
+        return Err(ApiError::bad_request(anyhow!(
+            "happening_from must not be after happening_to"
+        )));
+    }
+
+    let upcoming_cutoff = upcoming_cutoff();
+
+    let query = events::Entity::find()
+        .join(JoinType::InnerJoin, events::Relation::Organizers.def())
+        .apply_if(near.as_ref(), |query, near| {
+            query.filter(
+                geo::organizer_distance_km_expr(near.latitude, near.longitude).lte(near.radius_km),
+            )
+        })
+        .apply_if(non_empty(countries), |query, countries| {
+            query.filter(organizers::Column::Country.is_in(countries))
+        })
+        .apply_if(non_empty(exclude_countries), |query, countries| {
+            query.filter(organizers::Column::Country.is_not_in(countries))
+        })
+        .apply_if(non_empty(cities), |query, cities| {
+            query.filter(organizers::Column::City.is_in(cities))
+        })
+        .apply_if(non_empty(exclude_cities), |query, cities| {
+            query.filter(organizers::Column::City.is_not_in(cities))
+        })
+        .apply_if(non_empty(areas), |query, areas| {
+            query.filter(organizers::Column::Area.is_in(areas))
+        })
+        .apply_if(non_empty(exclude_areas), |query, areas| {
+            query.filter(organizers::Column::Area.is_not_in(areas))
+        })
+        .apply_if(non_empty(organizer_ids), |query, organizer_ids| {
+            query.filter(events::Column::OrganizerId.is_in(organizer_ids))
+        })
+        .apply_if(non_empty(exclude_organizer_ids), |query, organizer_ids| {
+            query.filter(events::Column::OrganizerId.is_not_in(organizer_ids))
+        })
+        .apply_if(non_empty(kinds), |query, kinds| {
+            query.filter(events::Column::Kind.is_in(kinds))
+        })
+        .apply_if(non_empty(exclude_kinds), |query, kinds| {
+            query.filter(events::Column::Kind.is_not_in(kinds))
+        })
+        .apply_if(happening_from, |query, from| {
+            query.filter(events::Column::HappeningAt.gte(from))
+        })
+        .apply_if(happening_to, |query, to| {
+            query.filter(events::Column::HappeningAt.lte(to))
+        })
+        .apply_if(non_empty(weekdays), |query, weekdays| {
+            query.filter(local_weekday_expr().is_in(weekdays.into_iter().map(Weekday::iso_number)))
+        })
+        .apply_if(
+            local_time_window(local_time_from, local_time_to),
+            |query, window| query.filter(window),
+        )
+        .apply_if(state, |query, state| match state {
+            EventState::Upcoming => query.filter(events::Column::HappeningAt.gt(upcoming_cutoff)),
+            EventState::Past => query.filter(events::Column::HappeningAt.lt(upcoming_cutoff)),
+        });
+
+    Ok(query)
+}
+
+/// See [`EventsSort::direction`].
+pub fn default_direction(sort_by: EventsSortBy, state: Option<EventState>) -> SortDirection {
+    match (sort_by, state) {
+        (EventsSortBy::HappeningAt, Some(EventState::Past)) => SortDirection::Desc,
+        (EventsSortBy::CreatedAt | EventsSortBy::UpdatedAt | EventsSortBy::Relevance, _) => {
+            SortDirection::Desc
+        }
+        _ => SortDirection::Asc,
+    }
+}
+
+/// Matches the events after `cursor` in its sort order.
+pub fn keyset_expr(cursor: &EventsCursor) -> Expr {
+    let column = match cursor.sort_by {
+        EventsSortBy::CreatedAt => r#""events"."created_at""#,
+        EventsSortBy::UpdatedAt => r#""events"."updated_at""#,
+        _ => r#""events"."happening_at""#,
+    };
+    let operator = match cursor.direction {
+        SortDirection::Asc => ">",
+        SortDirection::Desc => "<",
+    };
+    Expr::cust_with_values(
+        format!(r#"({column}, "events"."id") {operator} ($1, $2)"#),
+        [Value::from(cursor.value), Value::from(cursor.id)],
+    )
+}
+
+/// Events starting after this moment count as upcoming.
+pub fn upcoming_cutoff() -> DateTime<Utc> {
+    Utc::now() - Duration::hours(UPCOMING_GRACE_PERIOD_HOURS)
+}
+
+/// Reads a list filter that is also given as a single value.
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(
+        Option::<OneOrMany<T>>::deserialize(deserializer)?.map(|values| match values {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }),
+    )
+}
+
+/// Treats an empty list the same as an omitted filter.
+pub fn non_empty<T>(values: Option<Vec<T>>) -> Option<Vec<T>> {
+    values.filter(|values| !values.is_empty())
+}
+
+fn local_weekday_expr() -> Expr {
+    Expr::cust(format!(
+        "EXTRACT(ISODOW FROM {LOCAL_HAPPENING_AT_SQL})::integer"
+    ))
+}
+
+fn local_time_expr() -> Expr {
+    Expr::cust(format!("{LOCAL_HAPPENING_AT_SQL}::time"))
+}
+
+fn local_time_window(from: Option<NaiveTime>, to: Option<NaiveTime>) -> Option<Condition> {
+    match (from, to) {
+        (None, None) => None,
+        (Some(from), None) => Some(Condition::all().add(local_time_expr().gte(from))),
+        (None, Some(to)) => Some(Condition::all().add(local_time_expr().lte(to))),
+        (Some(from), Some(to)) if from <= to => Some(
+            Condition::all()
+                .add(local_time_expr().gte(from))
+                .add(local_time_expr().lte(to)),
+        ),
+        (Some(from), Some(to)) => Some(
+            Condition::any()
+                .add(local_time_expr().gte(from))
+                .add(local_time_expr().lte(to)),
+        ),
+    }
+}
+
+fn validate_geo_filter(near: &GeoFilter) -> Result<(), ApiError> {
+    if !(-90.0..=90.0).contains(&near.latitude) {
+        return Err(ApiError::bad_request(anyhow!(
+            "latitude must be between -90 and 90"
+        )));
+    }
+    if !(-180.0..=180.0).contains(&near.longitude) {
+        return Err(ApiError::bad_request(anyhow!(
+            "longitude must be between -180 and 180"
+        )));
+    }
+    if !near.radius_km.is_finite() || near.radius_km <= 0.0 {
+        return Err(ApiError::bad_request(anyhow!(
+            "radius_km must be a positive number"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_singular_filters() {
+        let request: EventsSearchRequest = serde_json::from_value(serde_json::json!({
+            "filters": {
+                "country": "DE",
+                "city": "Berlin",
+                "area": "BE",
+                "organizer_id": 7,
+                "kind": "League Cup",
+            },
+            "page": 1,
+            "page_size": 10,
+        }))
+        .unwrap();
+
+        let filters = &request.filters;
+        assert_eq!(filters.countries, Some(vec!["DE".to_string()]));
+        assert_eq!(filters.cities, Some(vec!["Berlin".to_string()]));
+        assert_eq!(filters.areas, Some(vec!["BE".to_string()]));
+        assert_eq!(filters.organizer_ids, Some(vec![7]));
+        assert_eq!(filters.kinds, Some(vec![EventKind::LeagueCup]));
+
+        let sql = filter_query(request.filters)
+            .unwrap()
+            .build(DbBackend::Postgres)
+            .to_string();
+        assert!(sql.contains(r#""organizers"."country" IN ('DE')"#), "{sql}");
+        assert!(
+            sql.contains(r#""organizers"."city" IN ('Berlin')"#),
+            "{sql}"
+        );
+        assert!(sql.contains(r#""events"."organizer_id" IN (7)"#), "{sql}");
+    }
+
+    #[test]
+    fn test_list_filters() {
+        let filters: EventsSearchFilters = serde_json::from_value(serde_json::json!({
+            "countries": ["DE", "AT"],
+            "kinds": [],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            filters.countries,
+            Some(vec!["DE".to_string(), "AT".to_string()])
+        );
+        assert_eq!(filters.kinds, Some(vec![]));
+        assert_eq!(filters.cities, None);
+    }
+}
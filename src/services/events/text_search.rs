@@ -0,0 +1,79 @@
+use sea_orm::sea_query::Expr;
+
+/// Search documents of the event and its joined organizer, see the `add_full_text_search`
+/// migration for how the vectors and their GIN indexes are built.
+const EVENT_VECTOR_SQL: &str = r#""events"."search_vector""#;
+const ORGANIZER_VECTOR_SQL: &str = r#""organizers"."search_vector""#;
+
+/// Turns free user input into a `to_tsquery` expression where every word is matched as a prefix,
+/// so that "pokemon cent" already finds "Pokémon Center". Returns `None` when nothing searchable
+/// is left.
+pub fn prefix_tsquery(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" & "))
+    }
+}
+
+fn tsquery_sql(placeholder: &str) -> String {
+    format!("to_tsquery('simple', immutable_unaccent({placeholder}))")
+}
+
+/// Matches each vector on its own, a match on the concatenated vectors could not use their
+/// indexes.
+pub fn matches_expr(tsquery: &str) -> Expr {
+    Expr::cust_with_values(
+        format!(
+            "({EVENT_VECTOR_SQL} @@ {query} OR {ORGANIZER_VECTOR_SQL} @@ {query})",
+            query = tsquery_sql("$1")
+        ),
+        [tsquery],
+    )
+}
+
+pub fn rank_expr(tsquery: &str) -> Expr {
+    Expr::cust_with_values(
+        format!(
+            "ts_rank({EVENT_VECTOR_SQL} || {ORGANIZER_VECTOR_SQL}, {})",
+            tsquery_sql("$1")
+        ),
+        [tsquery],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_tsquery() {
+        assert_eq!(
+            prefix_tsquery("Pokémon Centre Köln"),
+            Some("pokémon:* & centre:* & köln:*".to_string())
+        );
+        assert_eq!(
+            prefix_tsquery("  o'brien & (cards) | !"),
+            Some("o:* & brien:* & cards:*".to_string())
+        );
+        assert_eq!(prefix_tsquery(" -:*& "), None);
+    }
+
+    #[test]
+    fn test_matches_expr_filters_each_vector() {
+        let sql = sea_orm::sea_query::Query::select()
+            .expr(matches_expr("koln:*"))
+            .to_string(sea_orm::sea_query::PostgresQueryBuilder);
+
+        assert_eq!(
+            sql,
+            r#"SELECT ("events"."search_vector" @@ to_tsquery('simple', immutable_unaccent('koln:*')) OR "organizers"."search_vector" @@ to_tsquery('simple', immutable_unaccent('koln:*')))"#
+        );
+    }
+}
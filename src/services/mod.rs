@@ -1 +1,8 @@
+pub mod admin;
 pub mod events;
+pub mod notifications;
+pub mod organizers;
+pub mod retry;
+pub mod signing;
+pub mod subscriptions;
+pub mod users;
@@ -0,0 +1,294 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::Connections;
+use crate::entities::{events, organizers};
+use crate::services::notifications::discord_webhook::DiscordWebhookDelivery;
+use crate::services::notifications::email::EmailDelivery;
+use crate::services::retry::RetryPolicy;
+use crate::services::subscriptions::destination::SubscriptionDestination;
+
+/// A single event matched by a subscription.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub subscription_id: i32,
+    pub user_id: i32,
+    pub event: events::Model,
+    pub organizer: organizers::Model,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeliveryError {
+    /// The destination may accept the notification later, e.g. after a timeout or rate limit.
+    #[error("temporary delivery failure: {error}")]
+    Retryable {
+        error: anyhow::Error,
+        retry_after: Option<Duration>,
+    },
+    /// The destination will never accept the notification, retrying is pointless.
+    #[error("permanent delivery failure: {0}")]
+    Permanent(anyhow::Error),
+    /// The destination no longer exists, e.g. a deleted webhook, so the subscription is disabled.
+    #[error("invalid destination: {0}")]
+    InvalidDestination(anyhow::Error),
+}
+
+/// Sends notifications to a subscription destination.
+#[async_trait]
+pub trait Delivery: Send + Sync {
+    async fn deliver(
+        &self,
+        destination: &SubscriptionDestination,
+        notification: &Notification,
+    ) -> Result<(), DeliveryError>;
+
+    /// Sends several notifications of one subscription at once, channels without digests
+    /// deliver them one by one.
+    async fn deliver_digest(
+        &self,
+        destination: &SubscriptionDestination,
+        notifications: &[Notification],
+    ) -> Result<(), DeliveryError> {
+        for notification in notifications {
+            self.deliver(destination, notification).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Routes each notification to the channel of its destination.
+#[derive(Debug)]
+pub struct ChannelDelivery {
+    pub discord_webhook: DiscordWebhookDelivery,
+    /// `None` when SMTP is not configured.
+    pub email: Option<EmailDelivery>,
+}
+
+impl ChannelDelivery {
+    pub fn from_env(conns: &Connections) -> Result<Self, anyhow::Error> {
+        let email = EmailDelivery::from_env(conns)?;
+        if email.is_none() {
+            warn!("SMTP_URL is not set, email notifications stay queued until it is");
+        }
+
+        Ok(Self {
+            discord_webhook: DiscordWebhookDelivery::default(),
+            email,
+        })
+    }
+
+    /// A missing SMTP configuration is temporary, the notifications are released rather than
+    /// dropped and go out once it is configured.
+    fn email(&self) -> Result<&EmailDelivery, DeliveryError> {
+        self.email.as_ref().ok_or_else(|| DeliveryError::Retryable {
+            error: anyhow!("no delivery is configured for email destinations"),
+            retry_after: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Delivery for ChannelDelivery {
+    async fn deliver(
+        &self,
+        destination: &SubscriptionDestination,
+        notification: &Notification,
+    ) -> Result<(), DeliveryError> {
+        match destination {
+            SubscriptionDestination::DiscordWebhook { url } => {
+                self.discord_webhook.send(url, notification).await
+            }
+            SubscriptionDestination::Email { .. } => {
+                self.email()?.send(std::slice::from_ref(notification)).await
+            }
+        }
+    }
+
+    async fn deliver_digest(
+        &self,
+        destination: &SubscriptionDestination,
+        notifications: &[Notification],
+    ) -> Result<(), DeliveryError> {
+        match destination {
+            SubscriptionDestination::Email { .. } => self.email()?.send(notifications).await,
+            _ => {
+                for notification in notifications {
+                    self.deliver(destination, notification).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Runs `send` until it succeeds, retrying temporary failures with exponential backoff.
+pub async fn with_retry<F, Fut>(
+    policy: RetryPolicy,
+    subscription_id: i32,
+    mut send: F,
+) -> Result<(), DeliveryError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), DeliveryError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match send().await {
+            Err(DeliveryError::Retryable { error, retry_after })
+                if attempt < policy.max_attempts =>
+            {
+                let backoff = retry_after.unwrap_or_else(|| policy.backoff(attempt));
+                warn!(
+                    error = %error,
+                    attempt,
+                    subscription_id,
+                    "retrying notification delivery in {backoff:?}"
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Fails with the queued errors first, last one first, then records successful deliveries.
+    #[derive(Default)]
+    pub struct FakeDelivery {
+        pub failures: Mutex<Vec<DeliveryError>>,
+        pub attempts: Mutex<u32>,
+        pub delivered: Mutex<Vec<i32>>,
+        /// Number of notifications of each digest.
+        pub digests: Mutex<Vec<usize>>,
+    }
+
+    impl FakeDelivery {
+        pub fn retryable() -> DeliveryError {
+            DeliveryError::Retryable {
+                error: anyhow!("timeout"),
+                retry_after: None,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Delivery for FakeDelivery {
+        async fn deliver(
+            &self,
+            _destination: &SubscriptionDestination,
+            notification: &Notification,
+        ) -> Result<(), DeliveryError> {
+            *self.attempts.lock().unwrap() += 1;
+            if let Some(err) = self.failures.lock().unwrap().pop() {
+                return Err(err);
+            }
+            self.delivered.lock().unwrap().push(notification.event.id);
+            Ok(())
+        }
+
+        async fn deliver_digest(
+            &self,
+            _destination: &SubscriptionDestination,
+            notifications: &[Notification],
+        ) -> Result<(), DeliveryError> {
+            *self.attempts.lock().unwrap() += 1;
+            if let Some(err) = self.failures.lock().unwrap().pop() {
+                return Err(err);
+            }
+            self.digests.lock().unwrap().push(notifications.len());
+            Ok(())
+        }
+    }
+
+    pub fn notification() -> Notification {
+        let now = DateTime::parse_from_rfc3339("2026-10-24T08:00:00+00:00").unwrap();
+        Notification {
+            subscription_id: 1,
+            user_id: 1,
+            event: events::Model {
+                id: 7,
+                organizer_id: 3,
+                kind: "League Cup".to_string(),
+                name: "League Cup Köln".to_string(),
+                pokemon_event_slug: "league-cup-koln".to_string(),
+                guid: Uuid::new_v4(),
+                league: None,
+                happening_at: now,
+                created_at: now,
+                updated_at: now,
+            },
+            organizer: organizers::Model {
+                id: 3,
+                name: "Cards".to_string(),
+                address: "Main 1".to_string(),
+                city: "Köln".to_string(),
+                area: "NRW".to_string(),
+                country: "DE".to_string(),
+                latitude: 50.9,
+                longitude: 6.9,
+                timezone: "Europe/Berlin".to_string(),
+                created_at: now,
+                updated_at: now,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_retry() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        };
+        let destination = SubscriptionDestination::Email { digest: None };
+        let notification = notification();
+
+        let delivery = FakeDelivery::default();
+        delivery
+            .failures
+            .lock()
+            .unwrap()
+            .extend([FakeDelivery::retryable(), FakeDelivery::retryable()]);
+        assert!(
+            with_retry(policy, 1, || delivery.deliver(&destination, &notification))
+                .await
+                .is_ok()
+        );
+        assert_eq!(*delivery.attempts.lock().unwrap(), 3);
+        assert_eq!(*delivery.delivered.lock().unwrap(), vec![7]);
+
+        let delivery = FakeDelivery::default();
+        delivery.failures.lock().unwrap().extend([
+            FakeDelivery::retryable(),
+            FakeDelivery::retryable(),
+            FakeDelivery::retryable(),
+        ]);
+        assert!(matches!(
+            with_retry(policy, 1, || delivery.deliver(&destination, &notification)).await,
+            Err(DeliveryError::Retryable { .. })
+        ));
+        assert_eq!(*delivery.attempts.lock().unwrap(), 3);
+
+        let delivery = FakeDelivery::default();
+        delivery
+            .failures
+            .lock()
+            .unwrap()
+            .push(DeliveryError::Permanent(anyhow!("gone")));
+        assert!(matches!(
+            with_retry(policy, 1, || delivery.deliver(&destination, &notification)).await,
+            Err(DeliveryError::Permanent(_))
+        ));
+        assert_eq!(*delivery.attempts.lock().unwrap(), 1);
+    }
+}
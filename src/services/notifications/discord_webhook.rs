@@ -0,0 +1,225 @@
+use anyhow::anyhow;
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::services::events::presentation;
+use crate::services::notifications::delivery::{DeliveryError, Notification};
+
+const USERNAME: &str = "Pokémon TCG events";
+/// Pokémon yellow.
+const EMBED_COLOR: u32 = 0xFFCB05;
+const MAX_TITLE_LENGTH: usize = 256;
+const MAX_FIELD_LENGTH: usize = 1024;
+
+/// Posts notifications as embeds to Discord channel webhooks.
+#[derive(Debug, Clone)]
+pub struct DiscordWebhookDelivery {
+    client: reqwest::Client,
+}
+
+impl Default for DiscordWebhookDelivery {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                // Webhook URLs are validated up front, a redirect must not lead anywhere else.
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("valid HTTP client"),
+        }
+    }
+}
+
+/// Body of Discord's 429 responses.
+#[derive(Debug, Deserialize)]
+struct RateLimited {
+    /// Seconds until the webhook accepts requests again.
+    retry_after: f64,
+}
+
+impl DiscordWebhookDelivery {
+    pub async fn send(&self, url: &str, notification: &Notification) -> Result<(), DeliveryError> {
+        let response = self
+            .client
+            .post(url)
+            .json(&payload(notification))
+            .send()
+            .await
+            .map_err(|err| DeliveryError::Retryable {
+                error: err.into(),
+                retry_after: None,
+            })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => {
+                let header = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<f64>().ok());
+                let body = response
+                    .json::<RateLimited>()
+                    .await
+                    .ok()
+                    .map(|body| body.retry_after);
+
+                Err(DeliveryError::Retryable {
+                    error: anyhow!("rate limited by Discord"),
+                    retry_after: body
+                        .or(header)
+                        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                        .map(Duration::from_secs_f64),
+                })
+            }
+            // Deleted webhooks answer 404, webhooks with a revoked token 401 or 403.
+            StatusCode::NOT_FOUND | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(
+                DeliveryError::InvalidDestination(anyhow!("Discord webhook responded {status}")),
+            ),
+            status if status.is_server_error() => Err(DeliveryError::Retryable {
+                error: anyhow!("Discord webhook responded {status}"),
+                retry_after: None,
+            }),
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                Err(DeliveryError::Permanent(anyhow!(
+                    "Discord webhook responded {status}: {body}"
+                )))
+            }
+        }
+    }
+}
+
+fn payload(notification: &Notification) -> serde_json::Value {
+    let Notification {
+        event, organizer, ..
+    } = notification;
+    let local_happening_at = presentation::local_happening_at(event, organizer);
+
+    let store = [&organizer.name, &organizer.address, &organizer.city]
+        .into_iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    json!({
+        "username": USERNAME,
+        "allowed_mentions": { "parse": [] },
+        "embeds": [{
+            "title": truncate(&event.name, MAX_TITLE_LENGTH),
+            "url": presentation::event_url(event),
+            "color": EMBED_COLOR,
+            "timestamp": event.happening_at.to_rfc3339(),
+            "fields": [
+                { "name": "Kind", "value": truncate(&event.kind, MAX_FIELD_LENGTH), "inline": true },
+                {
+                    "name": "When",
+                    "value": format!(
+                        "{} ({})",
+                        local_happening_at.format("%a %-d %b %Y, %H:%M"),
+                        organizer.timezone
+                    ),
+                    "inline": true,
+                },
+                { "name": "Store", "value": truncate(&store, MAX_FIELD_LENGTH) },
+            ],
+        }],
+    })
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        return value.to_string();
+    }
+
+    let mut truncated = value.chars().take(max_chars - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::notifications::delivery::tests::notification;
+    use axum::{Json, Router, http::HeaderMap, routing::post};
+
+    #[test]
+    fn test_payload() {
+        let payload = payload(&notification());
+        let embed = &payload["embeds"][0];
+
+        assert_eq!(embed["title"], "League Cup Köln");
+        assert_eq!(embed["url"], "https://events.pokemon.com/league-cup-koln");
+        assert_eq!(embed["fields"][0]["value"], "League Cup");
+        assert_eq!(
+            embed["fields"][1]["value"],
+            "Sat 24 Oct 2026, 10:00 (Europe/Berlin)"
+        );
+        assert_eq!(embed["fields"][2]["value"], "Cards\nMain 1\nKöln");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let app = Router::new()
+            .route("/ok", post(|| async { axum::http::StatusCode::NO_CONTENT }))
+            .route(
+                "/limited",
+                post(|| async {
+                    let mut headers = HeaderMap::new();
+                    headers.insert(RETRY_AFTER, "2".parse().unwrap());
+                    (
+                        axum::http::StatusCode::TOO_MANY_REQUESTS,
+                        headers,
+                        Json(json!({ "message": "You are being rate limited.", "retry_after": 1.5, "global": false })),
+                    )
+                }),
+            )
+            .route("/deleted", post(|| async { axum::http::StatusCode::NOT_FOUND }))
+            .route(
+                "/broken",
+                post(|| async { axum::http::StatusCode::BAD_GATEWAY }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let delivery = DiscordWebhookDelivery::default();
+        let notification = notification();
+
+        assert!(
+            delivery
+                .send(&format!("{base}/ok"), &notification)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            delivery.send(&format!("{base}/limited"), &notification).await,
+            Err(DeliveryError::Retryable { retry_after: Some(retry_after), .. })
+                if retry_after == Duration::from_millis(1500)
+        ));
+        assert!(matches!(
+            delivery
+                .send(&format!("{base}/deleted"), &notification)
+                .await,
+            Err(DeliveryError::InvalidDestination(_))
+        ));
+        assert!(matches!(
+            delivery
+                .send(&format!("{base}/broken"), &notification)
+                .await,
+            Err(DeliveryError::Retryable {
+                retry_after: None,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,456 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use futures_util::{StreamExt, stream};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::Connections;
+use crate::entities::{events, organizers, user_subscriptions};
+use crate::persistence::{
+    user_subscription_notifications_repository, user_subscriptions_repository,
+};
+use crate::services::events::search_service;
+use crate::services::notifications::delivery::{self, Delivery, DeliveryError, Notification};
+use crate::services::retry::RetryPolicy;
+use crate::services::subscriptions::destination::{DigestFrequency, SubscriptionDestination};
+use crate::services::subscriptions::management_service::Subscription;
+
+/// Upper bound of events notified per subscription and run, the rest follows on the next run.
+const MAX_EVENTS_PER_SUBSCRIPTION: u64 = 100;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DispatcherConfig {
+    pub interval: std::time::Duration,
+    pub retry: RetryPolicy,
+    /// Upper bound of subscriptions delivered at the same time.
+    pub concurrency: usize,
+}
+
+impl DispatcherConfig {
+    pub fn from_env() -> Self {
+        let interval_seconds = std::env::var("NOTIFICATIONS_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(60);
+        let max_attempts = std::env::var("NOTIFICATIONS_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(RetryPolicy::default().max_attempts);
+        let concurrency = std::env::var("NOTIFICATIONS_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(8);
+
+        Self {
+            interval: std::time::Duration::from_secs(std::cmp::max(interval_seconds, 1)),
+            retry: RetryPolicy {
+                max_attempts: std::cmp::max(max_attempts, 1),
+                ..Default::default()
+            },
+            concurrency: std::cmp::max(concurrency, 1),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DispatchStats {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+/// Runs the dispatcher in the background for the lifetime of the process.
+pub fn spawn(
+    conns: Connections,
+    delivery: Arc<dyn Delivery>,
+    config: DispatcherConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match dispatch(&conns, delivery.as_ref(), &config).await {
+                Ok(stats) if stats.delivered > 0 || stats.failed > 0 => {
+                    info!(stats.delivered, stats.failed, "Dispatched notifications");
+                }
+                Ok(_) => {}
+                Err(err) => error!(error = %err, "notification dispatch failed"),
+            }
+        }
+    })
+}
+
+/// Notifies every subscription about its matching events starting within `notify_before`.
+///
+/// Digest subscriptions get all their due events in one delivery, at most once per digest period,
+/// including the events starting before the next digest. Subscriptions are handled concurrently,
+/// so that one destination backing off does not hold up the others.
+pub async fn dispatch(
+    conns: &Connections,
+    delivery: &dyn Delivery,
+    config: &DispatcherConfig,
+) -> Result<DispatchStats, anyhow::Error> {
+    let now = Utc::now().fixed_offset();
+    let subscriptions = user_subscriptions_repository::all_enabled(&conns.db).await?;
+
+    // Every subscription runs to the end even when another one failed, a dropped delivery would
+    // leave its events claimed but never sent.
+    let results = stream::iter(subscriptions)
+        .map(|model| dispatch_subscription(conns, delivery, config, model, now))
+        .buffer_unordered(config.concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut stats = DispatchStats::default();
+    for result in results {
+        let subscription_stats = result?;
+        stats.delivered += subscription_stats.delivered;
+        stats.failed += subscription_stats.failed;
+    }
+
+    Ok(stats)
+}
+
+async fn dispatch_subscription(
+    conns: &Connections,
+    delivery: &dyn Delivery,
+    config: &DispatcherConfig,
+    model: user_subscriptions::Model,
+    now: DateTime<FixedOffset>,
+) -> Result<DispatchStats, anyhow::Error> {
+    let stats = DispatchStats::default();
+    let user_id = model.user_id;
+    let last_notified_at = model.last_notified_at;
+    let subscription = match Subscription::try_from(model) {
+        Ok(subscription) => subscription,
+        Err(err) => {
+            warn!(error = %err, "skipping unreadable subscription");
+            return Ok(stats);
+        }
+    };
+
+    // Subscriptions stored before a destination rule was tightened are not sent to anymore.
+    if let Err(err) = subscription.destination.validate() {
+        warn!(error = %err, subscription_id = subscription.id, "disabling subscription");
+        user_subscriptions_repository::disable(&conns.db, subscription.id, &err.to_string())
+            .await?;
+        return Ok(stats);
+    }
+
+    let digest = subscription.destination.digest();
+    if let Some(digest) = digest
+        && last_notified_at.is_some_and(|at| at + digest.period() > now)
+    {
+        return Ok(stats);
+    }
+
+    let rows = match due_events(conns, &subscription, now).await {
+        Ok(rows) => rows,
+        Err(err) => {
+            warn!(error = %err, subscription_id = subscription.id, "skipping subscription");
+            return Ok(stats);
+        }
+    };
+
+    let mut notifications = vec![];
+    for (event, organizer) in rows {
+        if user_subscription_notifications_repository::claim(&conns.db, subscription.id, event.id)
+            .await?
+        {
+            notifications.push(Notification {
+                subscription_id: subscription.id,
+                user_id,
+                event,
+                organizer,
+            });
+        }
+    }
+    if notifications.is_empty() {
+        return Ok(stats);
+    }
+
+    let outcome = deliver_claimed(
+        delivery,
+        config.retry,
+        subscription.id,
+        &subscription.destination,
+        &notifications,
+    )
+    .await;
+    release(conns, subscription.id, &outcome.released).await?;
+    if let Some(reason) = &outcome.disabled {
+        user_subscriptions_repository::disable(&conns.db, subscription.id, reason).await?;
+    }
+    if outcome.stats.delivered > 0 {
+        user_subscriptions_repository::touch_last_notified_at(&conns.db, subscription.id).await?;
+    }
+
+    Ok(outcome.stats)
+}
+
+/// What became of the claimed notifications of a subscription.
+#[derive(Debug, Default)]
+struct Outcome {
+    stats: DispatchStats,
+    /// Events whose claims are released, so that they are notified again on a later run.
+    released: Vec<i32>,
+    /// Why the subscription is to be disabled.
+    disabled: Option<String>,
+}
+
+/// Delivers claimed notifications one by one, or all at once to a digest destination.
+async fn deliver_claimed(
+    delivery: &dyn Delivery,
+    retry: RetryPolicy,
+    subscription_id: i32,
+    destination: &SubscriptionDestination,
+    notifications: &[Notification],
+) -> Outcome {
+    let digest = destination.digest().is_some();
+    let batch_size = if digest { notifications.len() } else { 1 };
+    let mut outcome = Outcome::default();
+    for (index, batch) in notifications.chunks(batch_size).enumerate() {
+        // A digest stays a digest even when only one event is due.
+        let result = delivery::with_retry(retry, subscription_id, || async {
+            match digest {
+                true => delivery.deliver_digest(destination, batch).await,
+                false => delivery.deliver(destination, &batch[0]).await,
+            }
+        })
+        .await;
+
+        let err = match result {
+            Ok(()) => {
+                outcome.stats.delivered += batch.len();
+                continue;
+            }
+            Err(err) => err,
+        };
+
+        outcome.stats.failed += batch.len();
+        warn!(
+            error = %err,
+            subscription_id,
+            events = batch.len(),
+            "failed to deliver notification"
+        );
+        match err {
+            // Stays claimed, retrying it on every run would never succeed.
+            DeliveryError::Permanent(_) => {}
+            DeliveryError::Retryable { .. } => {
+                outcome
+                    .released
+                    .extend(batch.iter().map(|notification| notification.event.id));
+            }
+            // Everything not sent yet is released so that it is still notified once the user
+            // fixes the destination and the subscription is enabled again.
+            DeliveryError::InvalidDestination(err) => {
+                outcome.released.extend(
+                    notifications[index * batch_size..]
+                        .iter()
+                        .map(|notification| notification.event.id),
+                );
+                outcome.disabled = Some(err.to_string());
+                break;
+            }
+        }
+    }
+
+    outcome
+}
+
+async fn release(
+    conns: &Connections,
+    subscription_id: i32,
+    event_ids: &[i32],
+) -> Result<(), anyhow::Error> {
+    for event_id in event_ids {
+        user_subscription_notifications_repository::release(&conns.db, subscription_id, *event_id)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Events of the subscription starting within its notification window that were not notified yet.
+async fn due_events(
+    conns: &Connections,
+    subscription: &Subscription,
+    now: DateTime<FixedOffset>,
+) -> Result<Vec<(events::Model, organizers::Model)>, anyhow::Error> {
+    let mut filters = subscription.search_filters.clone();
+    let window_end = window_end(
+        subscription.notify_before,
+        subscription.destination.digest(),
+        now,
+    );
+    filters.happening_from = Some(
+        filters
+            .happening_from
+            .map_or(now, |from| std::cmp::max(from, now)),
+    );
+    filters.happening_to = Some(
+        filters
+            .happening_to
+            .map_or(window_end, |to| std::cmp::min(to, window_end)),
+    );
+    if filters.happening_from > filters.happening_to {
+        return Ok(vec![]);
+    }
+
+    let rows = search_service::filter_query(filters)
+        .map_err(|err| err.error)?
+        .filter(Expr::cust_with_values(
+            r#"NOT EXISTS (SELECT 1 FROM "user_subscription_notifications" WHERE "user_subscription_notifications"."user_subscription_id" = $1 AND "user_subscription_notifications"."event_id" = "events"."id")"#,
+            [subscription.id],
+        ))
+        .order_by(events::Column::HappeningAt, Order::Asc)
+        .order_by(events::Column::Id, Order::Asc)
+        .limit(MAX_EVENTS_PER_SUBSCRIPTION)
+        .select_also(organizers::Entity)
+        .all(&conns.db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(event, organizer)| organizer.map(|organizer| (event, organizer)))
+        .collect())
+}
+
+/// Events starting up to this moment are notified by a run at `now`.
+///
+/// A digest looks ahead at least until the next digest is due, otherwise events starting between
+/// two digests would never be part of one.
+fn window_end(
+    notify_before: i32,
+    digest: Option<DigestFrequency>,
+    now: DateTime<FixedOffset>,
+) -> DateTime<FixedOffset> {
+    let notify_before = Duration::minutes(notify_before.into());
+    let look_ahead = match digest {
+        Some(digest) => std::cmp::max(notify_before, digest.period()),
+        None => notify_before,
+    };
+
+    now + look_ahead
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::notifications::delivery::tests::{FakeDelivery, notification};
+    use anyhow::anyhow;
+
+    fn notifications() -> Vec<Notification> {
+        let mut second = notification();
+        second.event.id = 8;
+        vec![notification(), second]
+    }
+
+    const RETRY: RetryPolicy = RetryPolicy {
+        max_attempts: 2,
+        initial_backoff: std::time::Duration::from_millis(1),
+        max_backoff: std::time::Duration::from_millis(1),
+    };
+
+    #[tokio::test]
+    async fn test_deliver_claimed_sends_digests() {
+        let destination = SubscriptionDestination::Email {
+            digest: Some(DigestFrequency::Daily),
+        };
+        let delivery = FakeDelivery::default();
+
+        let outcome = deliver_claimed(&delivery, RETRY, 1, &destination, &[notification()]).await;
+        assert_eq!(outcome.stats.delivered, 1);
+        assert_eq!(*delivery.digests.lock().unwrap(), vec![1]);
+        assert!(delivery.delivered.lock().unwrap().is_empty());
+
+        let outcome = deliver_claimed(&delivery, RETRY, 1, &destination, &notifications()).await;
+        assert_eq!(outcome.stats.delivered, 2);
+        assert_eq!(*delivery.digests.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_claimed_releases_retryable_failures() {
+        let destination = SubscriptionDestination::Email { digest: None };
+        let delivery = FakeDelivery::default();
+        delivery
+            .failures
+            .lock()
+            .unwrap()
+            .extend([FakeDelivery::retryable(), FakeDelivery::retryable()]);
+
+        let outcome = deliver_claimed(&delivery, RETRY, 1, &destination, &notifications()).await;
+        assert_eq!(outcome.stats.delivered, 1);
+        assert_eq!(outcome.stats.failed, 1);
+        assert_eq!(outcome.released, vec![7]);
+        assert_eq!(outcome.disabled, None);
+        assert_eq!(*delivery.delivered.lock().unwrap(), vec![8]);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_claimed_keeps_permanent_failures_claimed() {
+        let destination = SubscriptionDestination::Email { digest: None };
+        let delivery = FakeDelivery::default();
+        delivery
+            .failures
+            .lock()
+            .unwrap()
+            .push(DeliveryError::Permanent(anyhow!("rejected")));
+
+        let outcome = deliver_claimed(&delivery, RETRY, 1, &destination, &notifications()).await;
+        assert_eq!(outcome.stats.delivered, 1);
+        assert_eq!(outcome.stats.failed, 1);
+        assert!(outcome.released.is_empty());
+        assert_eq!(outcome.disabled, None);
+        assert_eq!(*delivery.attempts.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_claimed_disables_invalid_destinations() {
+        let destination = SubscriptionDestination::Email { digest: None };
+        let delivery = FakeDelivery::default();
+        delivery
+            .failures
+            .lock()
+            .unwrap()
+            .push(DeliveryError::InvalidDestination(anyhow!(
+                "webhook deleted"
+            )));
+
+        let outcome = deliver_claimed(&delivery, RETRY, 1, &destination, &notifications()).await;
+        assert_eq!(outcome.stats.delivered, 0);
+        assert_eq!(outcome.stats.failed, 1);
+        assert_eq!(outcome.released, vec![7, 8]);
+        assert_eq!(outcome.disabled.as_deref(), Some("webhook deleted"));
+        assert_eq!(*delivery.attempts.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_window_end() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T08:00:00Z").unwrap();
+        let day = 24 * 60;
+
+        assert_eq!(window_end(day, None, now), now + Duration::days(1));
+        assert_eq!(
+            window_end(day, Some(DigestFrequency::Daily), now),
+            now + Duration::days(1)
+        );
+        assert_eq!(
+            window_end(14 * day, Some(DigestFrequency::Weekly), now),
+            now + Duration::days(14)
+        );
+
+        // A weekly digest notifying a day ahead covers the events starting before the next
+        // digest, e.g. three days after this one.
+        let next_digest = now + DigestFrequency::Weekly.period();
+        let between_digests = now + Duration::days(3);
+        let window_end = window_end(day, Some(DigestFrequency::Weekly), now);
+        assert!(between_digests <= window_end);
+        assert!(next_digest <= window_end);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod delivery;
+pub mod discord_webhook;
+pub mod dispatcher;
+pub mod email;
@@ -0,0 +1,204 @@
+use anyhow::anyhow;
+use chrono::{DateTime, FixedOffset};
+use itertools::Itertools;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::events::EventKind;
+use crate::entities::organizers;
+use crate::error::ApiError;
+use crate::persistence::{events_repository, organizers_repository};
+use crate::services::events::search_service::{
+    self as events_search_service, EventState, EventsSearchFilters, EventsSearchRequest,
+    EventsSearchResponse, non_empty,
+};
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrganizersSearchFilters {
+    pub countries: Option<Vec<String>>,
+    pub areas: Option<Vec<String>>,
+    pub cities: Option<Vec<String>>,
+    /// Case- and accent-insensitive substring of the organizer name.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizersSearchRequest {
+    pub filters: OrganizersSearchFilters,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizersSearchResponse {
+    pub organizers: Vec<OrganizerFull>,
+    pub total: u64,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizerFull {
+    pub organizer: organizers::Model,
+    pub stats: OrganizerStats,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrganizerStats {
+    pub total_events: u64,
+    pub next_event_at: Option<DateTime<FixedOffset>>,
+    /// Every kind of event the organizer has hosted or announced.
+    pub kinds: Vec<EventKind>,
+}
+
+pub async fn search(
+    conns: &Connections,
+    request: OrganizersSearchRequest,
+) -> Result<OrganizersSearchResponse, ApiError> {
+    let OrganizersSearchFilters {
+        countries,
+        areas,
+        cities,
+        name,
+    } = request.filters;
+    let page = Ord::max(request.page, 1);
+    let page_size = Ord::min(request.page_size, 100);
+
+    let query = organizers::Entity::find()
+        .apply_if(non_empty(countries), |query, countries| {
+            query.filter(organizers::Column::Country.is_in(countries))
+        })
+        .apply_if(non_empty(areas), |query, areas| {
+            query.filter(organizers::Column::Area.is_in(areas))
+        })
+        .apply_if(non_empty(cities), |query, cities| {
+            query.filter(organizers::Column::City.is_in(cities))
+        })
+        .apply_if(
+            name.as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty()),
+            |query, name| query.filter(name_contains_expr(name)),
+        )
+        .order_by(organizers::Column::Name, Order::Asc)
+        .order_by(organizers::Column::Id, Order::Asc);
+
+    let organizers = query
+        .clone()
+        .limit(Some(page_size))
+        .offset(page.saturating_sub(1) * page_size)
+        .all(&conns.db)
+        .await?;
+
+    let total = if page <= 1 && (organizers.len() as u64) < page_size {
+        organizers.len() as u64
+    } else {
+        query.count(&conns.db).await?
+    };
+
+    let mut stats_map = stats_by_organizer_id(
+        conns,
+        organizers.iter().map(|organizer| organizer.id).collect(),
+    )
+    .await?;
+
+    Ok(OrganizersSearchResponse {
+        organizers: organizers
+            .into_iter()
+            .map(|organizer| OrganizerFull {
+                stats: stats_map.remove(&organizer.id).unwrap_or_default(),
+                organizer,
+            })
+            .collect(),
+        total,
+        page,
+        page_size,
+    })
+}
+
+pub async fn find(conns: &Connections, id: i32) -> Result<OrganizerFull, ApiError> {
+    let organizer = find_organizer(conns, id).await?;
+    let stats = stats_by_organizer_id(conns, vec![id])
+        .await?
+        .remove(&id)
+        .unwrap_or_default();
+
+    Ok(OrganizerFull { organizer, stats })
+}
+
+pub async fn events(
+    conns: &Connections,
+    id: i32,
+    state: EventState,
+    page: u64,
+    page_size: u64,
+) -> Result<EventsSearchResponse, ApiError> {
+    find_organizer(conns, id).await?;
+
+    events_search_service::search(
+        conns,
+        EventsSearchRequest {
+            filters: EventsSearchFilters {
+                organizer_ids: Some(vec![id]),
+                state: Some(state),
+                ..Default::default()
+            },
+            query: None,
+            sort: None,
+            page,
+            page_size,
+            cursor: None,
+            include_total: None,
+        },
+    )
+    .await
+}
+
+async fn find_organizer(conns: &Connections, id: i32) -> Result<organizers::Model, ApiError> {
+    organizers_repository::find_by_id(&conns.db, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(anyhow!("organizer {id} not found")))
+}
+
+async fn stats_by_organizer_id(
+    conns: &Connections,
+    organizer_ids: Vec<i32>,
+) -> Result<HashMap<i32, OrganizerStats>, ApiError> {
+    let rows = events_repository::organizer_kind_stats(
+        &conns.db,
+        organizer_ids,
+        events_search_service::upcoming_cutoff(),
+    )
+    .await?;
+
+    let mut map: HashMap<i32, OrganizerStats> = HashMap::new();
+    for row in rows.into_iter().sorted_by(|a, b| a.kind.cmp(&b.kind)) {
+        let stats = map.entry(row.organizer_id).or_default();
+        stats.total_events += row.total_events as u64;
+        stats.next_event_at = match (stats.next_event_at, row.next_event_at) {
+            (Some(current), Some(next)) => Some(Ord::min(current, next)),
+            (current, next) => current.or(next),
+        };
+        if let Ok(kind) = EventKind::from_str(&row.kind) {
+            stats.kinds.push(kind);
+        }
+    }
+
+    Ok(map)
+}
+
+fn name_contains_expr(name: &str) -> Expr {
+    let escaped = name
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    Expr::cust_with_values(
+        r#"immutable_unaccent("organizers"."name") ILIKE immutable_unaccent($1)"#,
+        [format!("%{escaped}%")],
+    )
+}
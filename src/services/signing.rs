@@ -0,0 +1,59 @@
+use anyhow::Context;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs `message` with `APP_SECRET` so that links and cookies handed out cannot be forged.
+pub fn sign(message: &str) -> Result<String, anyhow::Error> {
+    Ok(sign_with(&secret()?, message))
+}
+
+/// Checks a signature produced by [`sign`] in constant time.
+pub fn verify(message: &str, signature: &str) -> Result<bool, anyhow::Error> {
+    Ok(verify_with(&secret()?, message, signature))
+}
+
+/// Fails when `APP_SECRET` is not set, so that features signing with it can refuse to start.
+pub fn check_secret() -> Result<(), anyhow::Error> {
+    secret().map(|_| ())
+}
+
+fn secret() -> Result<Vec<u8>, anyhow::Error> {
+    std::env::var("APP_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+        .map(String::into_bytes)
+        .context("APP_SECRET is not set")
+}
+
+fn sign_with(secret: &[u8], message: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(message.as_bytes());
+    URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+fn verify_with(secret: &[u8], message: &str, signature: &str) -> bool {
+    let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(message.as_bytes());
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signature = sign_with(b"secret", "unsubscribe:1");
+
+        assert!(verify_with(b"secret", "unsubscribe:1", &signature));
+        assert!(!verify_with(b"secret", "unsubscribe:2", &signature));
+        assert!(!verify_with(b"other", "unsubscribe:1", &signature));
+        assert!(!verify_with(b"secret", "unsubscribe:1", "not base64!"));
+    }
+}
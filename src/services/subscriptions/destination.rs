@@ -0,0 +1,154 @@
+use anyhow::{anyhow, bail};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use utoipa::ToSchema;
+
+/// Hosts serving Discord webhooks, the server posts to nothing else on behalf of users.
+const DISCORD_WEBHOOK_HOSTS: [&str; 4] = [
+    "discord.com",
+    "discordapp.com",
+    "ptb.discord.com",
+    "canary.discord.com",
+];
+
+/// Where notifications of a subscription are delivered, stored in
+/// `user_subscriptions.destination`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "channel", rename_all = "snake_case")]
+pub enum SubscriptionDestination {
+    /// Posts to a Discord channel webhook.
+    DiscordWebhook { url: String },
+    /// Sends to the verified email address of the user's Google account.
+    Email {
+        /// Groups matching events into one email per period instead of one email per event.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        digest: Option<DigestFrequency>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn period(self) -> Duration {
+        match self {
+            Self::Daily => Duration::days(1),
+            Self::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+impl SubscriptionDestination {
+    pub fn digest(&self) -> Option<DigestFrequency> {
+        match self {
+            Self::DiscordWebhook { .. } => None,
+            Self::Email { digest } => *digest,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        match self {
+            Self::DiscordWebhook { url } => validate_discord_webhook_url(url),
+            Self::Email { .. } => Ok(()),
+        }
+    }
+}
+
+/// Accepts `https://discord.com/api/webhooks/{id}/{token}` and its alternative hosts only.
+fn validate_discord_webhook_url(url: &str) -> Result<(), anyhow::Error> {
+    let url = Url::parse(url).map_err(|err| anyhow!("invalid webhook URL: {err}"))?;
+    if url.scheme() != "https" {
+        bail!("webhook URL must use https");
+    }
+    if url.port().is_some()
+        || !url.username().is_empty()
+        || url.password().is_some()
+        || !url
+            .host_str()
+            .is_some_and(|host| DISCORD_WEBHOOK_HOSTS.contains(&host))
+    {
+        bail!("webhook URL must point to discord.com");
+    }
+
+    let segments = url.path_segments().map(Iterator::collect::<Vec<_>>);
+    match segments.as_deref() {
+        Some(["api", "webhooks", id, token])
+            if !id.is_empty()
+                && id.chars().all(|c| c.is_ascii_digit())
+                && !token.is_empty()
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            Ok(())
+        }
+        _ => bail!("webhook URL must look like https://discord.com/api/webhooks/{{id}}/{{token}}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination_serde() {
+        let destination: SubscriptionDestination = serde_json::from_value(serde_json::json!({
+            "channel": "discord_webhook",
+            "url": "https://discord.com/api/webhooks/1/abc",
+        }))
+        .unwrap();
+        assert!(destination.validate().is_ok());
+
+        let destination: SubscriptionDestination =
+            serde_json::from_value(serde_json::json!({ "channel": "email" })).unwrap();
+        assert_eq!(destination, SubscriptionDestination::Email { digest: None });
+
+        let destination: SubscriptionDestination =
+            serde_json::from_value(serde_json::json!({ "channel": "email", "digest": "weekly" }))
+                .unwrap();
+        assert_eq!(destination.digest(), Some(DigestFrequency::Weekly));
+
+        assert!(
+            serde_json::from_value::<SubscriptionDestination>(
+                serde_json::json!({ "channel": "carrier_pigeon" })
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_discord_webhook_url() {
+        for url in [
+            "https://discord.com/api/webhooks/123/abc-DEF_1",
+            "https://discordapp.com/api/webhooks/123/abc",
+            "https://ptb.discord.com/api/webhooks/123/abc",
+            "https://canary.discord.com/api/webhooks/123/abc",
+        ] {
+            assert!(validate_discord_webhook_url(url).is_ok(), "{url}");
+        }
+
+        for url in [
+            "http://discord.com/api/webhooks/123/abc",
+            "https://example.com/api/webhooks/123/abc",
+            "https://169.254.169.254/api/webhooks/123/abc",
+            "https://localhost/api/webhooks/123/abc",
+            "https://discord.com.evil.example/api/webhooks/123/abc",
+            "https://evil.example#@discord.com/api/webhooks/123/abc",
+            "https://user@discord.com/api/webhooks/123/abc",
+            "https://discord.com:8443/api/webhooks/123/abc",
+            "https://discord.com/api/users/123/abc",
+            "https://discord.com/api/webhooks/abc/abc",
+            "https://discord.com/api/webhooks/123/",
+            "https://discord.com/api/webhooks/123/abc/extra",
+            "https://discord.com/api/webhooks/123/..%2F..%2Fusers",
+            "not a url",
+        ] {
+            assert!(validate_discord_webhook_url(url).is_err(), "{url}");
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, anyhow};
+use chrono::{DateTime, FixedOffset, Utc};
+use sea_orm::Set;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::{user_subscriptions, users};
+use crate::error::ApiError;
+use crate::persistence::{user_subscriptions_repository, users_repository};
+use crate::services::events::search_service::{self, EventsSearchFilters};
+use crate::services::subscriptions::destination::SubscriptionDestination;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionRequest {
+    pub destination: SubscriptionDestination,
+    /// Events matching these filters are notified.
+    pub search_filters: EventsSearchFilters,
+    /// Minutes before an event starts when it is notified.
+    pub notify_before: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Subscription {
+    pub id: i32,
+    pub destination: SubscriptionDestination,
+    pub search_filters: EventsSearchFilters,
+    pub notify_before: i32,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+    /// Set when notifications stopped, e.g. because the destination no longer exists.
+    /// Updating the subscription enables it again.
+    pub disabled_at: Option<DateTime<FixedOffset>>,
+    pub disabled_reason: Option<String>,
+}
+
+impl TryFrom<user_subscriptions::Model> for Subscription {
+    type Error = anyhow::Error;
+
+    fn try_from(model: user_subscriptions::Model) -> Result<Self, Self::Error> {
+        Ok(Subscription {
+            id: model.id,
+            destination: serde_json::from_value(model.destination)
+                .with_context(|| format!("invalid destination of subscription {}", model.id))?,
+            search_filters: serde_json::from_value(model.search_filters)
+                .with_context(|| format!("invalid search filters of subscription {}", model.id))?,
+            notify_before: model.notify_before,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+            disabled_at: model.disabled_at,
+            disabled_reason: model.disabled_reason,
+        })
+    }
+}
+
+pub async fn list(conns: &Connections, user: &users::Model) -> Result<Vec<Subscription>, ApiError> {
+    let models = user_subscriptions_repository::all_by_user_id(&conns.db, user.id).await?;
+
+    Ok(models
+        .into_iter()
+        .map(Subscription::try_from)
+        .collect::<Result<_, _>>()?)
+}
+
+pub async fn create(
+    conns: &Connections,
+    user: &users::Model,
+    request: SubscriptionRequest,
+) -> Result<Subscription, ApiError> {
+    validate(&request)?;
+    check_recipient(conns, user, &request.destination).await?;
+
+    let now = Utc::now().fixed_offset();
+    let model = user_subscriptions::ActiveModel {
+        id: Default::default(),
+        user_id: Set(user.id),
+        destination: Set(to_json(&request.destination)?),
+        search_filters: Set(to_json(&request.search_filters)?),
+        notify_before: Set(request.notify_before),
+        created_at: Set(now),
+        updated_at: Set(now),
+        disabled_at: Set(None),
+        disabled_reason: Set(None),
+        last_notified_at: Set(None),
+    };
+    let model = user_subscriptions_repository::insert(&conns.db, model).await?;
+
+    Ok(Subscription::try_from(model)?)
+}
+
+pub async fn update(
+    conns: &Connections,
+    user: &users::Model,
+    id: i32,
+    request: SubscriptionRequest,
+) -> Result<Subscription, ApiError> {
+    validate(&request)?;
+
+    let existing = find(conns, user, id).await?;
+    check_recipient(conns, user, &request.destination).await?;
+    let model = user_subscriptions::ActiveModel {
+        id: Set(existing.id),
+        destination: Set(to_json(&request.destination)?),
+        search_filters: Set(to_json(&request.search_filters)?),
+        notify_before: Set(request.notify_before),
+        updated_at: Set(Utc::now().fixed_offset()),
+        disabled_at: Set(None),
+        disabled_reason: Set(None),
+        ..Default::default()
+    };
+    let model = user_subscriptions_repository::update(&conns.db, model).await?;
+
+    Ok(Subscription::try_from(model)?)
+}
+
+pub async fn delete(conns: &Connections, user: &users::Model, id: i32) -> Result<(), ApiError> {
+    let existing = find(conns, user, id).await?;
+    user_subscriptions_repository::delete(&conns.db, existing.id).await?;
+
+    Ok(())
+}
+
+async fn find(
+    conns: &Connections,
+    user: &users::Model,
+    id: i32,
+) -> Result<user_subscriptions::Model, ApiError> {
+    user_subscriptions_repository::find_by_id_and_user_id(&conns.db, id, user.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(anyhow!("subscription {id} not found")))
+}
+
+fn validate(request: &SubscriptionRequest) -> Result<(), ApiError> {
+    request
+        .destination
+        .validate()
+        .map_err(ApiError::bad_request)?;
+    if request.notify_before <= 0 {
+        return Err(ApiError::bad_request(anyhow!(
+            "notify_before must be a positive number of minutes"
+        )));
+    }
+    // Builds the query once so that invalid filters are rejected now instead of by the dispatcher.
+    search_service::filter_query(request.search_filters.clone())?;
+
+    Ok(())
+}
+
+/// Email destinations need a verified Google email address to send to, the dispatcher would
+/// otherwise disable the subscription on its first notification.
+async fn check_recipient(
+    conns: &Connections,
+    user: &users::Model,
+    destination: &SubscriptionDestination,
+) -> Result<(), ApiError> {
+    if !matches!(destination, SubscriptionDestination::Email { .. }) {
+        return Ok(());
+    }
+    let google_user = users_repository::find_google_user(&conns.db, user.id).await?;
+    if google_user.is_none_or(|google_user| google_user.email_verified != Some(true)) {
+        return Err(ApiError::unprocessable_entity(anyhow!(
+            "email destinations need a Google account with a verified email address"
+        )));
+    }
+
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value, anyhow::Error> {
+    serde_json::to_value(value).map_err(anyhow::Error::from)
+}
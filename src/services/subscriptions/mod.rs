@@ -0,0 +1,3 @@
+pub mod destination;
+pub mod management_service;
+pub mod unsubscribe_service;
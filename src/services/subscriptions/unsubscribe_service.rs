@@ -0,0 +1,40 @@
+use anyhow::anyhow;
+
+use crate::Connections;
+use crate::error::ApiError;
+use crate::persistence::user_subscriptions_repository;
+use crate::services::signing;
+
+const DEFAULT_PUBLIC_URL: &str = "http://localhost:4400";
+
+/// Link that disables the subscription without signing in, put into every notification email.
+pub fn url(subscription_id: i32) -> Result<String, anyhow::Error> {
+    let public_url = std::env::var("PUBLIC_URL").unwrap_or(DEFAULT_PUBLIC_URL.to_string());
+    let token = signing::sign(&message(subscription_id))?;
+
+    Ok(format!(
+        "{}/subscriptions/{subscription_id}/unsubscribe?token={token}",
+        public_url.trim_end_matches('/')
+    ))
+}
+
+/// Checks the token of an unsubscribe link without changing anything.
+pub fn verify(id: i32, token: &str) -> Result<(), ApiError> {
+    if !signing::verify(&message(id), token)? {
+        return Err(ApiError::not_found(anyhow!("subscription {id} not found")));
+    }
+
+    Ok(())
+}
+
+pub async fn unsubscribe(conns: &Connections, id: i32, token: &str) -> Result<(), ApiError> {
+    verify(id, token)?;
+
+    user_subscriptions_repository::disable(&conns.db, id, "unsubscribed via email link").await?;
+
+    Ok(())
+}
+
+fn message(subscription_id: i32) -> String {
+    format!("unsubscribe:{subscription_id}")
+}
@@ -0,0 +1,294 @@
+use anyhow::{Context, anyhow};
+use chrono::Utc;
+use sea_orm::Set;
+use serde::Deserialize;
+use url::Url;
+
+use crate::Connections;
+use crate::entities::{discord_users, users};
+use crate::error::ApiError;
+use crate::persistence::{discord_users_repository, users_repository};
+use crate::services::users::session_service::{self, LoginResponse};
+
+const DEFAULT_AUTHORIZE_URL: &str = "https://discord.com/oauth2/authorize";
+const DEFAULT_API_URL: &str = "https://discord.com/api";
+const DEFAULT_CDN_URL: &str = "https://cdn.discordapp.com";
+const DEFAULT_PUBLIC_URL: &str = "http://localhost:4400";
+const SCOPES: &str = "identify email";
+
+/// OAuth2 application and endpoints, the URLs are configurable so that tests can point them to
+/// a stand-in server.
+#[derive(Debug, Clone)]
+pub struct DiscordOAuthConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_url: String,
+    pub authorize_url: String,
+    pub api_url: String,
+    pub cdn_url: String,
+}
+
+impl DiscordOAuthConfig {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let public_url = std::env::var("PUBLIC_URL").unwrap_or(DEFAULT_PUBLIC_URL.to_string());
+
+        Ok(Self {
+            client_id: std::env::var("DISCORD_CLIENT_ID")
+                .context("DISCORD_CLIENT_ID is not set")?,
+            client_secret: std::env::var("DISCORD_CLIENT_SECRET")
+                .context("DISCORD_CLIENT_SECRET is not set")?,
+            redirect_url: std::env::var("DISCORD_REDIRECT_URL").unwrap_or(format!(
+                "{}/auth/discord/callback",
+                public_url.trim_end_matches('/')
+            )),
+            authorize_url: std::env::var("DISCORD_AUTHORIZE_URL")
+                .unwrap_or(DEFAULT_AUTHORIZE_URL.to_string()),
+            api_url: std::env::var("DISCORD_API_URL").unwrap_or(DEFAULT_API_URL.to_string()),
+            cdn_url: std::env::var("DISCORD_CDN_URL").unwrap_or(DEFAULT_CDN_URL.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// The `/users/@me` object of the Discord API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DiscordProfile {
+    pub id: String,
+    pub username: String,
+    pub global_name: Option<String>,
+    pub avatar: Option<String>,
+    pub verified: Option<bool>,
+}
+
+impl DiscordProfile {
+    pub fn nickname(&self) -> &str {
+        self.global_name.as_deref().unwrap_or(&self.username)
+    }
+
+    pub fn avatar_url(&self, cdn_url: &str) -> Option<String> {
+        self.avatar.as_ref().map(|avatar| {
+            format!(
+                "{}/avatars/{}/{avatar}.png",
+                cdn_url.trim_end_matches('/'),
+                self.id
+            )
+        })
+    }
+}
+
+/// Where the user is sent to approve the login, `state` comes back to the callback unchanged.
+pub fn authorize_url(config: &DiscordOAuthConfig, state: &str) -> Result<String, anyhow::Error> {
+    let mut url = Url::parse(&config.authorize_url).context("invalid Discord authorize URL")?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &config.client_id)
+        .append_pair("redirect_uri", &config.redirect_url)
+        .append_pair("scope", SCOPES)
+        .append_pair("state", state);
+
+    Ok(url.into())
+}
+
+/// Completes the authorization-code flow, signing in or registering the Discord account.
+pub async fn login(
+    conns: &Connections,
+    config: &DiscordOAuthConfig,
+    code: &str,
+) -> Result<LoginResponse, ApiError> {
+    let client = reqwest::Client::new();
+    let access_token = exchange_code(&client, config, code).await?;
+    let profile = fetch_profile(&client, config, &access_token).await?;
+
+    let user = link_user(conns, config, &profile).await?;
+    Ok(session_service::create(conns, user).await?)
+}
+
+pub async fn exchange_code(
+    client: &reqwest::Client,
+    config: &DiscordOAuthConfig,
+    code: &str,
+) -> Result<String, ApiError> {
+    let response = client
+        .post(format!(
+            "{}/oauth2/token",
+            config.api_url.trim_end_matches('/')
+        ))
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &config.redirect_url),
+            ("client_id", &config.client_id),
+            ("client_secret", &config.client_secret),
+        ])
+        .send()
+        .await
+        .context("Discord token request failed")?;
+
+    if response.status().is_client_error() {
+        return Err(ApiError::unauthorized(anyhow!(
+            "Discord rejected the authorization code: {}",
+            response.status()
+        )));
+    }
+    let token = response
+        .error_for_status()
+        .context("Discord token request failed")?
+        .json::<TokenResponse>()
+        .await
+        .context("invalid Discord token response")?;
+
+    Ok(token.access_token)
+}
+
+pub async fn fetch_profile(
+    client: &reqwest::Client,
+    config: &DiscordOAuthConfig,
+    access_token: &str,
+) -> Result<DiscordProfile, anyhow::Error> {
+    client
+        .get(format!(
+            "{}/users/@me",
+            config.api_url.trim_end_matches('/')
+        ))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .context("Discord profile request failed")?
+        .json::<DiscordProfile>()
+        .await
+        .context("invalid Discord profile response")
+}
+
+/// Refreshes `discord_users` and returns the linked user, creating it on first login.
+async fn link_user(
+    conns: &Connections,
+    config: &DiscordOAuthConfig,
+    profile: &DiscordProfile,
+) -> Result<users::Model, anyhow::Error> {
+    let now = Utc::now().fixed_offset();
+    let discord_user = discord_users_repository::upsert(
+        &conns.db,
+        discord_users::ActiveModel {
+            id: Default::default(),
+            discord_id: Set(profile.id.clone()),
+            nickname: Set(profile.nickname().to_string()),
+            avatar_url: Set(profile.avatar_url(&config.cdn_url)),
+            is_verified: Set(profile.verified),
+            created_at: Set(now),
+        },
+    )
+    .await?;
+
+    if let Some(user) =
+        users_repository::find_by_discord_id(&conns.db, &discord_user.discord_id).await?
+    {
+        return Ok(user);
+    }
+
+    users_repository::insert(
+        &conns.db,
+        users::ActiveModel {
+            id: Default::default(),
+            username: Set(Some(discord_user.nickname)),
+            discord_id: Set(Some(discord_user.discord_id)),
+            google_id: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            role: Default::default(),
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Form, Json, Router,
+        http::{HeaderMap, StatusCode, header},
+        response::IntoResponse,
+        routing::{get, post},
+    };
+    use std::collections::HashMap;
+
+    fn config(base: &str) -> DiscordOAuthConfig {
+        DiscordOAuthConfig {
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_url: "http://localhost:4400/auth/discord/callback".to_string(),
+            authorize_url: format!("{base}/oauth2/authorize"),
+            api_url: format!("{base}/api"),
+            cdn_url: "https://cdn.example.com".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oauth_flow() {
+        let app = Router::new()
+            .route(
+                "/api/oauth2/token",
+                post(|Form(form): Form<HashMap<String, String>>| async move {
+                    if form.get("code").map(String::as_str) != Some("good")
+                        || form.get("client_secret").map(String::as_str) != Some("secret")
+                    {
+                        return (StatusCode::BAD_REQUEST, "invalid_grant").into_response();
+                    }
+                    Json(serde_json::json!({
+                        "access_token": "access",
+                        "token_type": "Bearer",
+                        "expires_in": 604800,
+                        "scope": "identify email",
+                    }))
+                    .into_response()
+                }),
+            )
+            .route(
+                "/api/users/@me",
+                get(|headers: HeaderMap| async move {
+                    if headers.get(header::AUTHORIZATION).unwrap() != "Bearer access" {
+                        return StatusCode::UNAUTHORIZED.into_response();
+                    }
+                    Json(serde_json::json!({
+                        "id": "80351110224678912",
+                        "username": "nelly",
+                        "global_name": "Nelly",
+                        "avatar": "8342729096ea3675442027381ff50dfe",
+                        "verified": true,
+                    }))
+                    .into_response()
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = config(&base);
+        let client = reqwest::Client::new();
+
+        let url = Url::parse(&authorize_url(&config, "xyz").unwrap()).unwrap();
+        let query = url.query_pairs().collect::<HashMap<_, _>>();
+        assert_eq!(query["client_id"], "client");
+        assert_eq!(query["state"], "xyz");
+        assert_eq!(query["redirect_uri"], config.redirect_url);
+
+        let error = exchange_code(&client, &config, "bad").await.unwrap_err();
+        assert_eq!(error.status_code, StatusCode::UNAUTHORIZED);
+
+        let access_token = exchange_code(&client, &config, "good").await.unwrap();
+        let profile = fetch_profile(&client, &config, &access_token)
+            .await
+            .unwrap();
+        assert_eq!(profile.id, "80351110224678912");
+        assert_eq!(profile.nickname(), "Nelly");
+        assert_eq!(profile.verified, Some(true));
+        assert_eq!(
+            profile.avatar_url(&config.cdn_url).unwrap(),
+            "https://cdn.example.com/avatars/80351110224678912/8342729096ea3675442027381ff50dfe.png"
+        );
+    }
+}
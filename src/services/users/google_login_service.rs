@@ -0,0 +1,414 @@
+use anyhow::{Context, anyhow};
+use chrono::Utc;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use reqwest::header::CACHE_CONTROL;
+use sea_orm::{IntoActiveModel, Set};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use url::Url;
+
+use crate::Connections;
+use crate::entities::{google_users, users};
+use crate::error::ApiError;
+use crate::persistence::{google_users_repository, users_repository};
+use crate::services::users::session_service::{self, LoginResponse};
+
+const DEFAULT_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const DEFAULT_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const DEFAULT_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+const DEFAULT_PUBLIC_URL: &str = "http://localhost:4400";
+const ISSUERS: [&str; 2] = ["accounts.google.com", "https://accounts.google.com"];
+const SCOPES: &str = "openid email profile";
+
+/// Used when Google does not send `Cache-Control: max-age`.
+const DEFAULT_KEYS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+/// Tokens signed by an unknown key trigger a refetch, but not more often than this.
+const MIN_KEYS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// OAuth client and endpoints, the URLs are configurable so that tests can point them to a
+/// stand-in server.
+#[derive(Debug, Clone)]
+pub struct GoogleOAuthConfig {
+    pub client_id: String,
+    /// Only needed for the authorization-code flow, ID tokens from Sign-In buttons are verified
+    /// with the client ID alone.
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    pub authorize_url: String,
+    pub token_url: String,
+}
+
+impl GoogleOAuthConfig {
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let public_url = std::env::var("PUBLIC_URL").unwrap_or(DEFAULT_PUBLIC_URL.to_string());
+
+        Ok(Self {
+            client_id: std::env::var("GOOGLE_CLIENT_ID").context("GOOGLE_CLIENT_ID is not set")?,
+            client_secret: std::env::var("GOOGLE_CLIENT_SECRET").ok(),
+            redirect_url: std::env::var("GOOGLE_REDIRECT_URL").unwrap_or(format!(
+                "{}/auth/google/callback",
+                public_url.trim_end_matches('/')
+            )),
+            authorize_url: std::env::var("GOOGLE_AUTHORIZE_URL")
+                .unwrap_or(DEFAULT_AUTHORIZE_URL.to_string()),
+            token_url: std::env::var("GOOGLE_TOKEN_URL").unwrap_or(DEFAULT_TOKEN_URL.to_string()),
+        })
+    }
+}
+
+/// Google's ID-token signing keys, cached for as long as Google's `Cache-Control` allows.
+#[derive(Debug)]
+pub struct GoogleKeys {
+    jwks_url: String,
+    client: reqwest::Client,
+    cache: RwLock<Option<CachedKeys>>,
+}
+
+#[derive(Debug)]
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+    max_age: Duration,
+}
+
+impl GoogleKeys {
+    pub fn new(jwks_url: String) -> Self {
+        Self {
+            jwks_url,
+            client: reqwest::Client::new(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("GOOGLE_JWKS_URL").unwrap_or(DEFAULT_JWKS_URL.to_string()))
+    }
+
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey, anyhow::Error> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(cached) = cache.as_ref()
+                && cached.fetched_at.elapsed() < cached.max_age
+                && let Some(jwk) = cached.keys.find(kid)
+            {
+                return DecodingKey::from_jwk(jwk).map_err(anyhow::Error::from);
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Keys rotate, so an unknown key ID refreshes the cache, unless another request just did.
+        let recently_fetched = cache
+            .as_ref()
+            .is_some_and(|cached| cached.fetched_at.elapsed() < MIN_KEYS_REFRESH_INTERVAL);
+        if !recently_fetched {
+            *cache = Some(self.fetch().await?);
+        }
+
+        let jwk = cache
+            .as_ref()
+            .and_then(|cached| cached.keys.find(kid))
+            .ok_or_else(|| anyhow!("unknown signing key {kid}"))?;
+        DecodingKey::from_jwk(jwk).map_err(anyhow::Error::from)
+    }
+
+    async fn fetch(&self) -> Result<CachedKeys, anyhow::Error> {
+        let response = self
+            .client
+            .get(&self.jwks_url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .context("Google JWKS request failed")?;
+
+        let max_age = response
+            .headers()
+            .get(CACHE_CONTROL)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_max_age)
+            .unwrap_or(DEFAULT_KEYS_MAX_AGE);
+        let keys = response
+            .json::<JwkSet>()
+            .await
+            .context("invalid Google JWKS response")?;
+
+        Ok(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+            max_age,
+        })
+    }
+}
+
+fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    cache_control
+        .split(',')
+        .find_map(|directive| directive.trim().strip_prefix("max-age="))
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Claims of a Google ID token that we keep.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleClaims {
+    pub sub: String,
+    pub email: String,
+    pub email_verified: Option<bool>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub picture: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Checks the signature, issuer, audience and expiry of an ID token.
+pub async fn verify_id_token(
+    keys: &GoogleKeys,
+    config: &GoogleOAuthConfig,
+    id_token: &str,
+) -> Result<GoogleClaims, ApiError> {
+    let header = jsonwebtoken::decode_header(id_token)
+        .map_err(|err| ApiError::unauthorized(anyhow!("malformed ID token: {err}")))?;
+    let kid = header
+        .kid
+        .ok_or_else(|| ApiError::unauthorized(anyhow!("ID token has no key ID")))?;
+    let key = keys
+        .decoding_key(&kid)
+        .await
+        .map_err(ApiError::unauthorized)?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&[&config.client_id]);
+    validation.set_issuer(&ISSUERS);
+
+    jsonwebtoken::decode::<GoogleClaims>(id_token, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|err| ApiError::unauthorized(anyhow!("invalid ID token: {err}")))
+}
+
+/// Where the user is sent to approve the login, `state` comes back to the callback unchanged.
+pub fn authorize_url(config: &GoogleOAuthConfig, state: &str) -> Result<String, anyhow::Error> {
+    let mut url = Url::parse(&config.authorize_url).context("invalid Google authorize URL")?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &config.client_id)
+        .append_pair("redirect_uri", &config.redirect_url)
+        .append_pair("scope", SCOPES)
+        .append_pair("state", state);
+
+    Ok(url.into())
+}
+
+/// Redeems an authorization code for the ID token of the signed-in user.
+pub async fn exchange_code(
+    client: &reqwest::Client,
+    config: &GoogleOAuthConfig,
+    code: &str,
+) -> Result<String, ApiError> {
+    let client_secret = config
+        .client_secret
+        .as_deref()
+        .context("GOOGLE_CLIENT_SECRET is not set")?;
+    let response = client
+        .post(&config.token_url)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &config.redirect_url),
+            ("client_id", &config.client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await
+        .context("Google token request failed")?;
+
+    if response.status().is_client_error() {
+        return Err(ApiError::unauthorized(anyhow!(
+            "Google rejected the authorization code: {}",
+            response.status()
+        )));
+    }
+    let token = response
+        .error_for_status()
+        .context("Google token request failed")?
+        .json::<TokenResponse>()
+        .await
+        .context("invalid Google token response")?;
+
+    Ok(token.id_token)
+}
+
+/// Signs in with a verified ID token.
+///
+/// With a `current_user` the Google account is attached to that user instead, so that someone
+/// who signed up through Discord does not end up with a second account.
+pub async fn login(
+    conns: &Connections,
+    keys: &GoogleKeys,
+    config: &GoogleOAuthConfig,
+    id_token: &str,
+    current_user: Option<users::Model>,
+) -> Result<LoginResponse, ApiError> {
+    let claims = verify_id_token(keys, config, id_token).await?;
+    let google_user = google_users_repository::upsert(
+        &conns.db,
+        google_users::ActiveModel {
+            id: Default::default(),
+            google_id: Set(claims.sub.clone()),
+            email: Set(claims.email.clone()),
+            email_verified: Set(claims.email_verified),
+            first_name: Set(claims.given_name.clone()),
+            last_name: Set(claims.family_name.clone()),
+            profile_image_url: Set(claims.picture.clone()),
+            created_at: Set(Utc::now().fixed_offset()),
+        },
+    )
+    .await?;
+
+    let owner = users_repository::find_by_google_id(&conns.db, &google_user.google_id).await?;
+    let user = match (current_user, owner) {
+        (Some(current), Some(owner)) if current.id != owner.id => {
+            return Err(ApiError::conflict(anyhow!(
+                "the Google account is linked to another user"
+            )));
+        }
+        (_, Some(owner)) => owner,
+        (Some(current), None) => {
+            if current.google_id.is_some() {
+                return Err(ApiError::conflict(anyhow!(
+                    "another Google account is linked to this user"
+                )));
+            }
+            let mut model = current.into_active_model();
+            model.google_id = Set(Some(google_user.google_id));
+            model.updated_at = Set(Utc::now().fixed_offset());
+            users_repository::update(&conns.db, model).await?
+        }
+        (None, None) => {
+            let now = Utc::now().fixed_offset();
+            let username = claims
+                .given_name
+                .clone()
+                .or_else(|| claims.email.split('@').next().map(str::to_string));
+            users_repository::insert(
+                &conns.db,
+                users::ActiveModel {
+                    id: Default::default(),
+                    username: Set(username),
+                    discord_id: Set(None),
+                    google_id: Set(Some(google_user.google_id)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    role: Default::default(),
+                },
+            )
+            .await?
+        }
+    };
+
+    Ok(session_service::create(conns, user).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, http::header, routing::get};
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use jsonwebtoken::{EncodingKey, Header};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PRIVATE_KEY: &str = include_str!("testdata/google_rsa_key.pem");
+    const MODULUS: &str = "yP9iN6GYAkAzd1XVTgmmolIZI14XbzjzqAJBisjfuw5rzwGdbXkre6fZDs_6R56Vy2FBawa2Si1UdwfaklwkUKELOyaUyg0sabcVI7YpfAxam1r7lCEXpyrZPFxQv2y7t4qXjFwt4hrh8JothAX2tMYSPmCSHgP4TFj35gmTL2PFSVeJWTeaRjPVTpb0mpb_vnvoLVL66SpfdUiNKRNK9YSWBjRx_a6iZR0OvsZGcshTeLQnrlb8tPOE9Z1ltGHaJ4nnH3hqQH__ndMMCdeJK-jm5VVXE1C3THA5kTnDfVucmPE1JYhV-Kfog87E0sQyOhEy-2cbOUBELMaj-N031w";
+
+    fn config() -> GoogleOAuthConfig {
+        GoogleOAuthConfig {
+            client_id: "client.apps.googleusercontent.com".to_string(),
+            client_secret: None,
+            redirect_url: "http://localhost:4400/auth/google/callback".to_string(),
+            authorize_url: DEFAULT_AUTHORIZE_URL.to_string(),
+            token_url: DEFAULT_TOKEN_URL.to_string(),
+        }
+    }
+
+    fn id_token(kid: &str, audience: &str) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        let claims = serde_json::json!({
+            "iss": "https://accounts.google.com",
+            "aud": audience,
+            "sub": "110169484474386276334",
+            "email": "nelly@example.com",
+            "email_verified": true,
+            "given_name": "Nelly",
+            "exp": Utc::now().timestamp() + 600,
+            "iat": Utc::now().timestamp(),
+        });
+        let key = EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap();
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_id_token() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let app = Router::new().route(
+            "/certs",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async {
+                    (
+                        [(
+                            header::CACHE_CONTROL,
+                            "public, max-age=19845, must-revalidate",
+                        )],
+                        Json(serde_json::json!({
+                            "keys": [{
+                                "kty": "RSA",
+                                "alg": "RS256",
+                                "use": "sig",
+                                "kid": "test-key",
+                                "n": MODULUS,
+                                "e": URL_SAFE_NO_PAD.encode([1, 0, 1]),
+                            }],
+                        })),
+                    )
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let keys = GoogleKeys::new(format!("{base}/certs"));
+        let config = config();
+
+        let claims = verify_id_token(&keys, &config, &id_token("test-key", &config.client_id))
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "110169484474386276334");
+        assert_eq!(claims.email_verified, Some(true));
+
+        assert!(
+            verify_id_token(&keys, &config, &id_token("test-key", "someone-else"))
+                .await
+                .is_err()
+        );
+        assert!(
+            verify_id_token(&keys, &config, &id_token("rotated-key", &config.client_id))
+                .await
+                .is_err()
+        );
+        // Served from the cache, the unknown key ID does not refetch within the refresh interval.
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        assert_eq!(
+            parse_max_age("public, max-age=19845, must-revalidate"),
+            Some(Duration::from_secs(19845))
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod discord_login_service;
+pub mod google_login_service;
+pub mod session_service;
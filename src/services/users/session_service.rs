@@ -0,0 +1,128 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::Connections;
+use crate::entities::{user_sessions, users};
+use crate::persistence::user_sessions_repository;
+use crate::services::signing;
+
+const DEFAULT_TTL_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginResponse {
+    /// Session token for the `Authorization: Bearer` header, it is not retrievable later.
+    pub token: String,
+    /// The token stops working afterwards and the user has to sign in again.
+    pub expires_at: DateTime<FixedOffset>,
+    pub user: UserProfile,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserProfile {
+    pub id: i32,
+    pub username: Option<String>,
+    pub discord_id: Option<String>,
+    pub google_id: Option<String>,
+    /// `user` or `admin`.
+    pub role: String,
+}
+
+impl From<users::Model> for UserProfile {
+    fn from(user: users::Model) -> Self {
+        UserProfile {
+            id: user.id,
+            username: user.username,
+            discord_id: user.discord_id,
+            google_id: user.google_id,
+            role: user.role,
+        }
+    }
+}
+
+/// Session tokens are only stored as SHA-256 hashes so a database leak does not leak sessions.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// How long sessions stay valid, configured by `SESSION_TTL_DAYS`.
+pub fn ttl() -> Duration {
+    let days = std::env::var("SESSION_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_TTL_DAYS);
+
+    Duration::days(days)
+}
+
+/// Starts a session for the user and returns its token.
+pub async fn create(
+    conns: &Connections,
+    user: users::Model,
+) -> Result<LoginResponse, anyhow::Error> {
+    let token = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+    let expires_at = (Utc::now() + ttl()).fixed_offset();
+    user_sessions_repository::insert(&conns.db, user.id, &hash_token(&token), expires_at).await?;
+
+    Ok(LoginResponse {
+        token,
+        expires_at,
+        user: user.into(),
+    })
+}
+
+/// Resolves the session token to its session and user, unless it expired or was revoked.
+pub async fn authenticate(
+    conns: &Connections,
+    token: &str,
+) -> Result<Option<(user_sessions::Model, users::Model)>, anyhow::Error> {
+    user_sessions_repository::find_active_by_token_hash(&conns.db, &hash_token(token)).await
+}
+
+/// Signs out a single session.
+pub async fn revoke(conns: &Connections, session_id: i32) -> Result<(), anyhow::Error> {
+    user_sessions_repository::revoke(&conns.db, session_id).await
+}
+
+/// Signs the user out everywhere, returns the number of revoked sessions.
+pub async fn revoke_all(conns: &Connections, user_id: i32) -> Result<u64, anyhow::Error> {
+    user_sessions_repository::revoke_all_by_user_id(&conns.db, user_id).await
+}
+
+/// The session cookie carries the token with its signature so that forged cookies are rejected
+/// without a database lookup.
+pub fn cookie_value(token: &str) -> Result<String, anyhow::Error> {
+    Ok(format!(
+        "{token}.{}",
+        signing::sign(&cookie_message(token))?
+    ))
+}
+
+/// Returns the token of a session cookie, `None` when its signature does not match.
+pub fn token_from_cookie(value: &str) -> Result<Option<String>, anyhow::Error> {
+    let Some((token, signature)) = value.split_once('.') else {
+        return Ok(None);
+    };
+
+    Ok(signing::verify(&cookie_message(token), signature)?.then(|| token.to_string()))
+}
+
+fn cookie_message(token: &str) -> String {
+    format!("session:{token}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}